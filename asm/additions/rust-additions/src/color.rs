@@ -4,6 +4,7 @@
 
 use crate::debug;
 use crate::lyt;
+use crate::settings;
 
 use core::arch::asm;
 use core::ffi::{c_char, c_void};
@@ -21,16 +22,19 @@ use static_assertions::assert_eq_size;
 // Always add an assert_eq_size!() macro after defining a struct to ensure it's
 // the size you expect it to be.
 
-//////////////////////
-// ADD STRUCTS HERE //
-//////////////////////
+// Indices into RandomizerSettings.sky_cloud_colors
+pub const DAYTIME_SKY: usize = 0;
+pub const NIGHTTIME_SKY: usize = 1;
+pub const DAYTIME_CLOUD: usize = 2;
+pub const NIGHTTIME_CLOUD: usize = 3;
+
+pub const RAINBOW_COLOR_INDEX: u8 = 11;
 
 // IMPORTANT: when using vanilla code, the start point must be declared in
 // symbols.yaml and then added to this extern block.
 extern "C" {
     static LYT_PAUSE_DISP: *mut lyt::dLytPauseDisp;
     static mut COLOR_CHANGE_DELAY: u8;
-    static SKY_CLOUD_COLORS: u32;
 
     static mut CURRENT_SKY_COLOR: u32;
     static mut DAYTIME_SKY_COLOR: u32;
@@ -65,28 +69,30 @@ pub fn init_rainbow_colors(memcpy_dest: *mut c_void, memcpy_src: *mut c_void) {
         // Replaced instructions
         memcpy(memcpy_dest, memcpy_src, 0x28A0);
 
+        let colors = get_sky_cloud_colors();
+
         // if daytime_sky_color == rainbow
-        if SKY_CLOUD_COLORS & 0xFF == 11 {
+        if colors[DAYTIME_SKY] == RAINBOW_COLOR_INDEX {
             DAYTIME_SKY_COLOR = 0xFF7373FF;
             TITLESCREEN_SKY_COLOR = 0xFF7373FF;
             THUNDERHEAD_SKY_COLOR = 0xFF7373FF;
             FILESELECT_SKY_COLOR = 0xFF7373FF;
         }
         // if nighttime_sky_color == rainbow
-        if (SKY_CLOUD_COLORS >> 8) & 0xFF == 11 {
+        if colors[NIGHTTIME_SKY] == RAINBOW_COLOR_INDEX {
             NIGHTTIME_SKY_COLOR = 0xFF7373FF;
             DEMISE_SKY_COLOR = 0xFF7373FF;
             HYLIA_SKY_COLOR = 0xFF7373FF;
         }
         // if daytime_cloud_color == rainbow
-        if (SKY_CLOUD_COLORS >> 16) & 0xFF == 11 {
+        if colors[DAYTIME_CLOUD] == RAINBOW_COLOR_INDEX {
             DAYTIME_CLOUD_COLOR = 0xFF73FF73;
             TITLESCREEN_CLOUD_COLOR = 0xFF73FF73;
             THUNDERHEAD_CLOUD_COLOR = 0xFF73FF73;
             FILESELECT_CLOUD_COLOR = 0xFF73FF73;
         }
         // if nighttime_cloud_color == rainbow
-        if (SKY_CLOUD_COLORS >> 24) & 0xFF == 11 {
+        if colors[NIGHTTIME_CLOUD] == RAINBOW_COLOR_INDEX {
             NIGHTTIME_CLOUD_COLOR = 0xFF73FF73;
             DEMISE_CLOUD_COLOR = 0xFF73FF73;
             HYLIA_CLOUD_COLOR = 0xFF73FF73;
@@ -103,28 +109,30 @@ pub fn handle_colors() {
         }
         COLOR_CHANGE_DELAY -= 1;
 
-        let mut color = get_color_from_index(SKY_CLOUD_COLORS & 0xFF, DAYTIME_SKY_COLOR);
+        let colors = get_sky_cloud_colors();
+
+        let mut color = get_color_from_index(colors[DAYTIME_SKY] as u32, DAYTIME_SKY_COLOR);
         if color != 0 {
             DAYTIME_SKY_COLOR = color;
             TITLESCREEN_SKY_COLOR = DAYTIME_SKY_COLOR;
             THUNDERHEAD_SKY_COLOR = DAYTIME_SKY_COLOR;
             FILESELECT_SKY_COLOR = DAYTIME_SKY_COLOR;
         }
-        color = get_color_from_index((SKY_CLOUD_COLORS >> 8) & 0xFF, NIGHTTIME_SKY_COLOR);
+        color = get_color_from_index(colors[NIGHTTIME_SKY] as u32, NIGHTTIME_SKY_COLOR);
         if color != 0 {
             NIGHTTIME_SKY_COLOR = color;
             DEMISE_SKY_COLOR = NIGHTTIME_SKY_COLOR;
             HYLIA_SKY_COLOR = NIGHTTIME_SKY_COLOR;
         }
 
-        color = get_color_from_index((SKY_CLOUD_COLORS >> 16) & 0xFF, DAYTIME_CLOUD_COLOR);
+        color = get_color_from_index(colors[DAYTIME_CLOUD] as u32, DAYTIME_CLOUD_COLOR);
         if color != 0 {
             DAYTIME_CLOUD_COLOR = color;
             TITLESCREEN_CLOUD_COLOR = DAYTIME_CLOUD_COLOR;
             THUNDERHEAD_CLOUD_COLOR = DAYTIME_CLOUD_COLOR;
             FILESELECT_CLOUD_COLOR = DAYTIME_CLOUD_COLOR;
         }
-        color = get_color_from_index((SKY_CLOUD_COLORS >> 24) & 0xFF, NIGHTTIME_CLOUD_COLOR);
+        color = get_color_from_index(colors[NIGHTTIME_CLOUD] as u32, NIGHTTIME_CLOUD_COLOR);
         if color != 0 {
             NIGHTTIME_CLOUD_COLOR = color;
             DEMISE_CLOUD_COLOR = NIGHTTIME_CLOUD_COLOR;
@@ -133,6 +141,14 @@ pub fn handle_colors() {
     }
 }
 
+// Falls back to the vanilla colors if the settings can't be read
+pub fn get_sky_cloud_colors() -> [u8; settings::SKY_CLOUD_COLORS_COUNT] {
+    match settings::get_randomizer_settings() {
+        Some(settings) => return settings.sky_cloud_colors,
+        None => return [0; settings::SKY_CLOUD_COLORS_COUNT],
+    }
+}
+
 #[no_mangle]
 pub fn get_color_from_index(color_index: u32, color: u32) -> u32 {
     match color_index {
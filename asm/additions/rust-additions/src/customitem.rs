@@ -0,0 +1,142 @@
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(unused)]
+
+use crate::debug;
+use crate::item::{ItemDisplayInfo, DEFAULT_ITEM_DISPLAY_INFO};
+
+use core::arch::asm;
+use core::ffi::{c_char, c_void, CStr};
+use cstr::cstr;
+use static_assertions::assert_eq_size;
+
+// repr(C) prevents rust from reordering struct fields.
+// packed(1) prevents rust from aligning structs to the size of the largest
+// field.
+
+// Using u64 or 64bit pointers forces structs to be 8-byte aligned.
+// The vanilla code seems to be 4-byte aligned. To make extra sure, used
+// packed(1) to force the alignment to match what you define.
+
+// Always add an assert_eq_size!() macro after defining a struct to ensure it's
+// the size you expect it to be.
+
+// Items that don't exist in vanilla. Their ids come after the dungeon items
+// and before the traps, custom-items.asm gives them a model index and an item
+// get animation
+pub const FIRST_CUSTOM_ITEMID: u16 = 214;
+pub const LAST_CUSTOM_ITEMID: u16 = 239;
+
+pub const FANFARE_NONE: u16 = 0xFFFF;
+
+// Everything that's special about a custom item. `textbox` is whether the item
+// shows its text when it's collected freestanding and `on_collect` sets
+// whatever flags or counters the item stands for
+#[derive(Copy, Clone)]
+pub struct CustomItem {
+    pub itemid:     u16,
+    pub arc_name:   &'static CStr,
+    pub model_name: &'static CStr,
+    pub display:    ItemDisplayInfo,
+    pub textbox:    bool,
+    pub fanfare:    u16,
+    pub on_collect: fn(),
+}
+
+// Same offsets as the small keys in data/item_display_info.yaml
+const KEY_RING_DISPLAY: ItemDisplayInfo = ItemDisplayInfo {
+    wall_offset: 5.0,
+    wall_rotation_x: 0x0C00,
+    wall_rotation_y: 0x1000,
+    wall_rotation_z: 0x0600,
+    ..DEFAULT_ITEM_DISPLAY_INFO
+};
+
+// The small keys are added from the DUNGEON_ITEMS table like every other
+// dungeon item, so key rings don't need a handler of their own
+const fn key_ring(itemid: u16) -> CustomItem {
+    return CustomItem {
+        itemid,
+        arc_name: cstr!("GetKeySmall"),
+        model_name: cstr!("GetKeySmallNormal"),
+        display: KEY_RING_DISPLAY,
+        textbox: true,
+        fanfare: FANFARE_NONE,
+        on_collect: do_nothing,
+    };
+}
+
+// Add new custom items here
+static CUSTOM_ITEMS: [CustomItem; 8] = [
+    CustomItem {
+        itemid:     214,
+        arc_name:   cstr!("Onp"),
+        model_name: cstr!("OnpB"),
+        display:    ItemDisplayInfo {
+            model_scale: 0.5,
+            ..DEFAULT_ITEM_DISPLAY_INFO
+        },
+        textbox:    true,
+        fanfare:    FANFARE_NONE,
+        // The group counter is incremented by the item get event, which runs
+        // for every way the item can be given
+        on_collect: do_nothing,
+    },
+    CustomItem {
+        itemid:     215,
+        arc_name:   cstr!("DesertRobot"),
+        model_name: cstr!("DesertRobot"),
+        display:    ItemDisplayInfo {
+            model_scale: 0.3,
+            ..DEFAULT_ITEM_DISPLAY_INFO
+        },
+        textbox:    true,
+        fanfare:    FANFARE_NONE,
+        // The Scrapper's storyflags are set by its item get event
+        on_collect: do_nothing,
+    },
+    key_ring(216), // SV Key Ring
+    key_ring(217), // LMF Key Ring
+    key_ring(218), // AC Key Ring
+    key_ring(219), // FS Key Ring
+    key_ring(220), // SSH Key Ring
+    key_ring(221), // SK Key Ring
+];
+
+// IMPORTANT: when using vanilla code, the start point must be declared in
+// symbols.yaml and then added to this extern block.
+extern "C" {
+    static FANFARE_SOUND_MGR: *mut c_void;
+
+    // Functions
+    fn debugPrint_128(string: *const c_char, fstr: *const c_char, ...);
+    fn playFanfareMaybe(soundMgr: *mut c_void, soundIndex: u16) -> u64;
+}
+
+// IMPORTANT: when adding functions here that need to get called from the game,
+// add `#[no_mangle]` and add a .global *symbolname* to
+// additions/rust-additions.asm
+
+pub fn get_custom_item(itemid: u16) -> Option<&'static CustomItem> {
+    if !(FIRST_CUSTOM_ITEMID..=LAST_CUSTOM_ITEMID).contains(&itemid) {
+        return None;
+    }
+    return CUSTOM_ITEMS
+        .iter()
+        .find(|custom_item| custom_item.itemid == itemid);
+}
+
+// Called from handle_custom_item_get for every item that's collected
+pub fn handle_custom_item_collected(itemid: u16) {
+    if let Some(custom_item) = get_custom_item(itemid) {
+        (custom_item.on_collect)();
+
+        if custom_item.fanfare != FANFARE_NONE {
+            unsafe {
+                playFanfareMaybe(FANFARE_SOUND_MGR, custom_item.fanfare);
+            }
+        }
+    }
+}
+
+fn do_nothing() {}
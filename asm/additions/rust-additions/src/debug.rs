@@ -72,6 +72,17 @@ pub fn debug_print_num(string: *const c_char, number: usize) {
     }
 }
 
+#[no_mangle]
+pub fn debug_print_nums(string: *const c_char, number1: usize, number2: usize) {
+    // e.g. debug::debug_print_nums(cstr!("scene: %d, flag: %d").as_ptr(), scene as
+    // usize, flag as usize);
+
+    unsafe {
+        let buffer: [c_char; 128] = [0; 128];
+        debugPrint_128(buffer.as_ptr(), string, number1, number2);
+    }
+}
+
 #[no_mangle]
 pub fn debug_print_float(string: *const c_char, float: f32) {
     // e.g. debug::debug_print_float(cstr!("param1: %f").as_ptr(), param1 as f32);
@@ -5,6 +5,7 @@
 use crate::actor;
 use crate::debug;
 use crate::flag;
+use crate::savefile;
 
 use core::arch::asm;
 use core::ffi::{c_char, c_void};
@@ -34,6 +35,250 @@ pub struct WarpToStartInfo {
 }
 assert_eq_size!([u8; 12], WarpToStartInfo);
 
+// ER fixup rules
+//
+// Written by the patcher from data/patches/erfixups.yaml and checked in order
+// by handle_er_cases. A rule applies when the next stage and entrance match
+// and all of its conditions hold. The table ends at the first rule with an
+// empty stage name.
+pub const MAX_ER_FIXUP_RULES: usize = 32;
+
+#[repr(u8)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum ER_FLAG_TYPE {
+    NONE      = 0,
+    STORYFLAG = 1,
+    SCENEFLAG = 2,
+}
+
+// Refers to `count` consecutive flags starting at `flag`
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct ErFlagRange {
+    pub flag_type:  ER_FLAG_TYPE,
+    pub value:      u8, // 1 = set, 0 = unset
+    pub sceneindex: u8, // only for sceneflags
+    pub count:      u8,
+    pub flag:       u16,
+}
+assert_eq_size!([u8; 6], ErFlagRange);
+
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct ErFixupRule {
+    pub stage_name:   [u8; 8],
+    pub entrance:     u8,
+    pub new_entrance: u8, // 0xFF keeps the entrance
+    pub _0:           [u8; 2],
+    pub conditions:   [ErFlagRange; 4],
+    pub effects:      [ErFlagRange; 4],
+}
+assert_eq_size!([u8; 60], ErFixupRule);
+
+// Night validity rules
+//
+// Written by the patcher from data/night_validity.yaml. The first rule
+// matching the next stage (and entrance) decides whether it can be entered at
+// night. Stage patterns ending in '*' match as a prefix. The table ends at the
+// first rule with an empty stage pattern.
+pub const MAX_NIGHT_VALIDITY_RULES: usize = 32;
+
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct NightValidityRule {
+    pub stage_pattern: [u8; 8],
+    pub allow:         u8,
+    pub entrances:     [u8; 7], // padded with 0xFF, all 0xFF matches any entrance
+}
+assert_eq_size!([u8; 16], NightValidityRule);
+
+impl NightValidityRule {
+    pub fn matches_next_stage(&self) -> bool {
+        unsafe {
+            for (i, &c) in self.stage_pattern.iter().enumerate() {
+                if c == b'*' {
+                    break;
+                }
+
+                if NEXT_STAGE_NAME[i] != c {
+                    return false;
+                }
+
+                if c == 0 {
+                    break;
+                }
+            }
+
+            if self.entrances[0] == 0xFF {
+                return true;
+            }
+
+            let next_entrance = NEXT_ENTRANCE;
+            return self.entrances.contains(&next_entrance);
+        }
+    }
+}
+
+// Entrance remaps
+//
+// Written by the patcher (e.g. from a plandomizer's entrance_remaps) so
+// entrances can be changed without patching the stage files. The first remap
+// whose `from` matches where triggerEntrance/triggerExit is about to take us
+// replaces it with `to`. The table ends at the first remap with an empty stage
+// name.
+pub const MAX_ENTRANCE_REMAPS: usize = 128;
+
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct EntranceRemap {
+    pub from_stage_name: [u8; 8],
+    pub from_room:       u8, // 0xFF matches any room
+    pub from_layer:      u8, // 0xFF matches any layer
+    pub from_entrance:   u8,
+    pub forced_night:    u8, // 0xFF doesn't force anything
+    pub to_stage_name:   [u8; 8],
+    pub to_room:         u8,
+    pub to_layer:        u8, // 0xFF keeps the layer
+    pub to_entrance:     u8,
+    pub forced_trial:    u8, // 0xFF doesn't force anything
+}
+assert_eq_size!([u8; 24], EntranceRemap);
+
+impl EntranceRemap {
+    pub fn matches_next_stage(&self) -> bool {
+        unsafe {
+            return next_stage_name_is(self.from_stage_name)
+                && (self.from_room == 0xFF || self.from_room == NEXT_ROOM)
+                && (self.from_layer == 0xFF || self.from_layer == NEXT_LAYER)
+                && self.from_entrance == NEXT_ENTRANCE;
+        }
+    }
+}
+
+// Fi warp destinations
+//
+// Written by the patcher (see util/warp_destinations.py) and selected from
+// Fi's warp menu with custom event command 76. A destination can only be
+// warped to once its unlock flag is set (NONE is always unlocked).
+// Destinations with `unlock_on_arrival` set their flag themselves the first
+// time the player enters them. The table ends at the first destination with
+// an empty stage name.
+pub const MAX_WARP_DESTINATIONS: usize = 48;
+
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct WarpDestination {
+    pub info:              WarpToStartInfo,
+    pub unlock:            ErFlagRange,
+    pub unlock_on_arrival: u8,
+    pub _0:                u8,
+}
+assert_eq_size!([u8; 20], WarpDestination);
+
+impl WarpDestination {
+    pub fn is_unlocked(&self) -> bool {
+        let unlock = self.unlock;
+        return unlock.holds();
+    }
+
+    pub fn matches_next_stage(&self) -> bool {
+        unsafe {
+            return next_stage_name_is(self.info.stage_name)
+                && self.info.room == NEXT_ROOM
+                && self.info.entrance == NEXT_ENTRANCE;
+        }
+    }
+}
+
+// Entrance history
+//
+// Every transition that goes through handle_er_cases is appended to a ring
+// buffer in the save file so entrance trackers can fill in the connections the
+// player has discovered (and still have them after reloading). `count` only
+// goes up, transition n is in records[n % ENTRANCE_HISTORY_CAPACITY].
+pub const ENTRANCE_HISTORY_CAPACITY: usize = 32;
+
+#[repr(u8)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum TRANSITION_KIND {
+    ENTRANCE = 0,
+    FI_WARP  = 1,
+}
+
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct EntranceTransition {
+    pub from_stage_name:       [u8; 8],
+    pub from_room:             u8,
+    pub from_layer:            u8,
+    pub from_arrival_entrance: u8, // how the from stage was entered, not the exit taken
+    pub kind:                  u8, // TRANSITION_KIND, kept as a u8 as it's read from the save
+    pub to_stage_name:         [u8; 8],
+    pub to_room:               u8,
+    pub to_layer:              u8,
+    pub to_entrance:           u8,
+    pub to_night:              u8,
+}
+assert_eq_size!([u8; 24], EntranceTransition);
+
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct EntranceHistory {
+    pub count:   u32,
+    pub records: [EntranceTransition; ENTRANCE_HISTORY_CAPACITY],
+}
+assert_eq_size!([u8; 772], EntranceHistory);
+
+impl ErFlagRange {
+    pub fn holds(&self) -> bool {
+        for flag in self.flag..self.flag + self.count as u16 {
+            let is_set = match self.flag_type {
+                ER_FLAG_TYPE::STORYFLAG => flag::check_storyflag(flag) != 0,
+                ER_FLAG_TYPE::SCENEFLAG => {
+                    flag::check_global_sceneflag(self.sceneindex as u16, flag) != 0
+                },
+                _ => continue,
+            };
+
+            if is_set != (self.value != 0) {
+                return false;
+            }
+        }
+
+        return true;
+    }
+
+    pub fn apply(&self) {
+        for flag in self.flag..self.flag + self.count as u16 {
+            match (self.flag_type, self.value != 0) {
+                (ER_FLAG_TYPE::STORYFLAG, true) => flag::set_storyflag(flag),
+                (ER_FLAG_TYPE::STORYFLAG, false) => flag::unset_storyflag(flag),
+                (ER_FLAG_TYPE::SCENEFLAG, true) => {
+                    flag::set_global_sceneflag(self.sceneindex as u16, flag)
+                },
+                (ER_FLAG_TYPE::SCENEFLAG, false) => {
+                    flag::unset_global_sceneflag(self.sceneindex as u16, flag)
+                },
+                _ => {},
+            }
+        }
+    }
+}
+
+impl ErFixupRule {
+    // Compares up to and including the null terminator of the rule's stage
+    pub fn matches_next_stage(&self) -> bool {
+        unsafe {
+            return next_stage_name_is(self.stage_name) && self.entrance == NEXT_ENTRANCE;
+        }
+    }
+
+    pub fn conditions_hold(&self) -> bool {
+        let conditions = self.conditions;
+        return conditions.iter().all(|condition| condition.holds());
+    }
+}
+
 // IMPORTANT: when using vanilla code, the start point must be declared in
 // symbols.yaml and then added to this extern block.
 extern "C" {
@@ -69,6 +314,11 @@ extern "C" {
 
     // Custom
     static WARP_TO_START_INFO: WarpToStartInfo;
+    static ER_FIXUP_RULES: [ErFixupRule; MAX_ER_FIXUP_RULES];
+    static NIGHT_VALIDITY_RULES: [NightValidityRule; MAX_NIGHT_VALIDITY_RULES];
+    static ENTRANCE_REMAPS: [EntranceRemap; MAX_ENTRANCE_REMAPS];
+    static WARP_DESTINATIONS: [WarpDestination; MAX_WARP_DESTINATIONS];
+    static mut SKIP_ENTRANCE_HISTORY: u8;
 
     // Functions
     fn debugPrint_128(string: *const c_char, fstr: *const c_char, ...);
@@ -110,6 +360,47 @@ extern "C" {
 // IMPORTANT: when adding functions here that need to get called from the game,
 // add `#[no_mangle]` and add a .global *symbolname* to
 // additions/rust-additions.asm
+
+// Compares up to and including the null terminator of `stage_name`
+pub fn next_stage_name_is(stage_name: [u8; 8]) -> bool {
+    unsafe {
+        let len = match stage_name.iter().position(|&c| c == 0) {
+            Some(pos) => pos + 1,
+            None => stage_name.len(),
+        };
+
+        return stage_name[..len] == NEXT_STAGE_NAME[..len];
+    }
+}
+
+// Returns the remap that was applied (if any) so handle_er_cases can force
+// the night and trial state once it's done with them
+#[no_mangle]
+pub fn apply_entrance_remaps() -> Option<EntranceRemap> {
+    unsafe {
+        for remap in ENTRANCE_REMAPS.iter() {
+            if remap.from_stage_name[0] == 0 {
+                break;
+            }
+
+            if !remap.matches_next_stage() {
+                continue;
+            }
+
+            NEXT_STAGE_NAME = remap.to_stage_name;
+            NEXT_ROOM = remap.to_room;
+            if remap.to_layer != 0xFF {
+                NEXT_LAYER = remap.to_layer;
+            }
+            NEXT_ENTRANCE = remap.to_entrance;
+
+            return Some(*remap);
+        }
+
+        return None;
+    }
+}
+
 // When checking/setting stage info in this function be sure to use
 // all of the NEXT_* variables as this function gets called right after
 // those have been assigned.
@@ -122,35 +413,28 @@ pub fn handle_er_cases() {
             (*GAME_RELOADER_PTR).speed_after_reload = 30f32;
         }
 
-        // If we're spawning from Sky Keep, but Sky Keep hasn't appeared yet,
-        // instead spawn near the statue
-        if &NEXT_STAGE_NAME[..5] == b"F000\0"
-            && NEXT_ENTRANCE == 53
-            && flag::check_storyflag(22) == 0
-        {
-            NEXT_ENTRANCE = 52
-        }
+        // Remap the entrance before anything else looks at the next stage
+        let remap = apply_entrance_remaps();
 
-        // // If we're spawning from LMF and it hasn't been raised,
-        // // instead spawn in front of where the dungeon entrance would be
-        if &NEXT_STAGE_NAME[..5] == b"F300\0" && NEXT_ENTRANCE == 5 && flag::check_storyflag(8) == 0
-        {
-            NEXT_ENTRANCE = 19;
-        }
+        // Redirect entrances that would softlock or don't exist yet and set
+        // any flags needed to make the next stage work
+        for rule in ER_FIXUP_RULES.iter() {
+            if rule.stage_name[0] == 0 {
+                break;
+            }
 
-        // If we're spawning in Lanayru Desert/Mines through the minecart entrance,
-        // make sure that a timeshift stone that makes the minecart move is active
-        if ((&NEXT_STAGE_NAME[..5] == b"F300\0" && NEXT_ENTRANCE == 2)
-            || (&NEXT_STAGE_NAME[..7] == b"F300_1\0" && NEXT_ENTRANCE == 1))
-            && (flag::check_global_sceneflag(7, 113) == 0
-                && flag::check_global_sceneflag(7, 114) == 0)
-        {
-            // Unset all other timeshift stones in the scene
-            for flag in (115..=124).chain([108, 111]) {
-                flag::unset_global_sceneflag(7, flag);
+            if !rule.matches_next_stage() || !rule.conditions_hold() {
+                continue;
+            }
+
+            if rule.new_entrance != 0xFF {
+                NEXT_ENTRANCE = rule.new_entrance;
+            }
+
+            let effects = rule.effects;
+            for effect in effects.iter() {
+                effect.apply();
             }
-            // Set the last timeshift stone in mines
-            flag::set_global_sceneflag(7, 113);
         }
 
         // If we're about to enter a stage that should have the silent realm effect
@@ -163,8 +447,8 @@ pub fn handle_er_cases() {
 
         // Force NEXT_NIGHT to day (storyflag keeps the night state stored)
         // If it should be night time, check if the entrance is valid at night
-        // check_storyflag(899) can only be true if natural_night_connections is off
-        if (flag::check_storyflag(899) != 0 || NEXT_NIGHT == 1) {
+        // The night storyflag can only be set if natural_night_connections is off
+        if (flag::check_night_storyflag() || NEXT_NIGHT == 1) {
             // debug::debug_print("Should be night");
 
             if next_stage_is_valid_at_night() {
@@ -179,6 +463,21 @@ pub fn handle_er_cases() {
             NEXT_NIGHT = 0;
         }
 
+        if let Some(remap) = remap {
+            if remap.forced_trial != 0xFF {
+                NEXT_TRIAL = remap.forced_trial;
+            }
+            if remap.forced_night != 0xFF {
+                NEXT_NIGHT = remap.forced_night;
+            }
+        }
+
+        unlock_warp_destinations_on_arrival();
+
+        if SKIP_ENTRANCE_HISTORY == 0 {
+            record_entrance_transition(TRANSITION_KIND::ENTRANCE);
+        }
+
         playFanfareMaybe(FANFARE_SOUND_MGR, 0xFFFF);
 
         // Replaced code sets these
@@ -192,26 +491,14 @@ pub fn handle_er_cases() {
 #[no_mangle]
 pub fn next_stage_is_valid_at_night() -> bool {
     unsafe {
-        if (&NEXT_STAGE_NAME[..2] == b"F0" &&      // Non-surface stage
-            &NEXT_STAGE_NAME[..6] != b"F004r\0" && // Not Bazaar
-            &NEXT_STAGE_NAME[..6] != b"F010r\0" && // Not Isle of Songs
-            &NEXT_STAGE_NAME[..6] != b"F019r\0" && // Not Bamboo Island
-            &NEXT_STAGE_NAME[..3] != b"F02"   ||   // Not Sky/Thunderhead
-            (
-                &NEXT_STAGE_NAME[..5] == b"F020\0" && // Sky stage
-                (
-                    NEXT_ENTRANCE == 0  || // Beedle's Island
-                    NEXT_ENTRANCE == 22 || // Lumpy West Door
-                    NEXT_ENTRANCE == 23 || // Lumpy East Door
-                    NEXT_ENTRANCE == 24    // Lumpy Back Door
-                )
-            ) ||
-            // Waterfall Cave
-            &NEXT_STAGE_NAME[..5] == b"D000\0" ||
-            // The Goddess's Silent Realm
-            &NEXT_STAGE_NAME[..5] == b"S000\0")
-        {
-            return true;
+        for rule in NIGHT_VALIDITY_RULES.iter() {
+            if rule.stage_pattern[0] == 0 {
+                break;
+            }
+
+            if rule.matches_next_stage() {
+                return rule.allow != 0;
+            }
         }
     }
 
@@ -238,14 +525,48 @@ pub fn handle_er_action_states() {
 #[no_mangle]
 pub fn warp_to_start() {
     unsafe {
-        let start_info = &*(&WARP_TO_START_INFO as *const WarpToStartInfo);
+        warp_to(&*(&WARP_TO_START_INFO as *const WarpToStartInfo));
+    }
+}
+
+// Whether `stage_name` is the stage the file starts in
+pub fn is_start_stage(stage_name: &[u8]) -> bool {
+    unsafe {
+        return stage_name == &WARP_TO_START_INFO.stage_name[..];
+    }
+}
+
+// Returns false (without warping) if the destination doesn't exist or hasn't
+// been unlocked yet
+#[no_mangle]
+pub fn warp_to_destination(index: u16) -> bool {
+    unsafe {
+        let destination = match WARP_DESTINATIONS.get(index as usize) {
+            Some(destination) => destination,
+            None => return false,
+        };
+
+        if destination.info.stage_name[0] == 0 || !destination.is_unlocked() {
+            return false;
+        }
+
+        warp_to(&destination.info);
+        return true;
+    }
+}
+
+pub fn warp_to(info: &WarpToStartInfo) {
+    unsafe {
+        // handle_er_cases runs from inside actuallyTriggerEntrance as well,
+        // before the destination is known
+        SKIP_ENTRANCE_HISTORY = 1;
 
         GameReloader__actuallyTriggerEntrance(
             STAGE_MGR,
-            (*start_info).room.into(),
-            (*start_info).layer.into(),
-            (*start_info).entrance.into(),
-            (*start_info).night.into(),
+            info.room.into(),
+            info.layer.into(),
+            info.entrance.into(),
+            info.night.into(),
             0,
             0,
             0xF,
@@ -254,11 +575,11 @@ pub fn warp_to_start() {
 
         (*STAGE_MGR).set_in_actually_trigger_entrance = 0;
 
-        NEXT_STAGE_NAME = (*start_info).stage_name;
-        NEXT_ROOM = (*start_info).room;
-        NEXT_LAYER = (*start_info).layer;
-        NEXT_ENTRANCE = (*start_info).entrance;
-        NEXT_NIGHT = (*start_info).night;
+        NEXT_STAGE_NAME = info.stage_name;
+        NEXT_ROOM = info.room;
+        NEXT_LAYER = info.layer;
+        NEXT_ENTRANCE = info.entrance;
+        NEXT_NIGHT = info.night;
 
         if (*GAME_RELOADER_PTR).reload_trigger == 0x2BF {
             (*GAME_RELOADER_PTR).reload_trigger = 5;
@@ -266,6 +587,56 @@ pub fn warp_to_start() {
 
         // Just to be extra safe (fixes some issues with Fi warp)
         handle_er_cases();
+
+        SKIP_ENTRANCE_HISTORY = 0;
+        record_entrance_transition(TRANSITION_KIND::FI_WARP);
+    }
+}
+
+// Records the transition from the current stage to the (final) next stage
+#[no_mangle]
+pub fn record_entrance_transition(kind: TRANSITION_KIND) {
+    unsafe {
+        // Loading a file from the title screen (layer 28) isn't a transition
+        if CURRENT_LAYER == 28 {
+            return;
+        }
+
+        let history = match savefile::get_rando_save_data() {
+            Some(rando) => &mut rando.entrance_history,
+            None => return,
+        };
+        let count = history.count;
+
+        history.records[count as usize % ENTRANCE_HISTORY_CAPACITY] = EntranceTransition {
+            from_stage_name:       CURRENT_STAGE_NAME,
+            from_room:             CURRENT_ROOM,
+            from_layer:            CURRENT_LAYER,
+            from_arrival_entrance: CURRENT_ENTRANCE,
+            kind:                  kind as u8,
+            to_stage_name:         NEXT_STAGE_NAME,
+            to_room:               NEXT_ROOM,
+            to_layer:              NEXT_LAYER,
+            to_entrance:           NEXT_ENTRANCE,
+            to_night:              NEXT_NIGHT,
+        };
+        history.count = count.wrapping_add(1);
+    }
+}
+
+#[no_mangle]
+pub fn unlock_warp_destinations_on_arrival() {
+    unsafe {
+        for destination in WARP_DESTINATIONS.iter() {
+            if destination.info.stage_name[0] == 0 {
+                break;
+            }
+
+            if destination.unlock_on_arrival != 0 && destination.matches_next_stage() {
+                let unlock = destination.unlock;
+                unlock.apply();
+            }
+        }
     }
 }
 
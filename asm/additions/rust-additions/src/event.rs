@@ -5,6 +5,8 @@
 use crate::debug;
 use crate::entrance;
 use crate::fix;
+use crate::flag;
+use crate::tracker;
 use crate::traps;
 
 use core::arch::asm;
@@ -103,6 +105,8 @@ assert_eq_size!([u8; 0x10], EventFlowElement);
 // IMPORTANT: when using vanilla code, the start point must be declared in
 // symbols.yaml and then added to this extern block.
 extern "C" {
+    static EVENT_MGR: *mut EventMgr;
+
     // Custom symbols
     static mut TRAP_ID: u8;
 
@@ -114,6 +118,13 @@ extern "C" {
 // add `#[no_mangle]` and add a .global *symbolname* to
 // additions/rust-additions.asm
 
+#[no_mangle]
+pub fn is_in_event() -> bool {
+    unsafe {
+        return !EVENT_MGR.is_null() && (*EVENT_MGR).probably_state != 0;
+    }
+}
+
 #[no_mangle]
 pub fn custom_event_commands(
     actor_event_flow_mgr: *mut ActorEventFlowMgr,
@@ -133,6 +144,69 @@ pub fn custom_event_commands(
         },
         72 => traps::update_traps(),
         73 => fix::set_skyloft_thunderhead_sceneflag(),
+        // Fi warp to destination (param1), result is 1 if it's still locked
+        76 => unsafe {
+            if entrance::warp_to_destination(event_flow_element.param1) {
+                (*actor_event_flow_mgr).result_from_previous_check = 0;
+            } else {
+                (*actor_event_flow_mgr).result_from_previous_check = 1;
+            }
+        },
+        // Vanilla can set tempflags (28) and set or unset zoneflags (4, 5)
+        77 => flag::unset_tempflag(event_flow_element.param1),
+        // Check tempflag (param1), result is 1 if it's set
+        78 => unsafe {
+            (*actor_event_flow_mgr).result_from_previous_check =
+                flag::check_tempflag(event_flow_element.param1).into();
+        },
+        // Check zoneflag (param1) in room (param2), result is 1 if it's set.
+        // Uses the current room if param2 is 0xFFFF
+        79 => unsafe {
+            let roomid = match event_flow_element.param2 {
+                0xFFFF => flag::get_current_room(),
+                // Too large rooms become 0xFF so check_zoneflag rejects them
+                roomid => roomid.try_into().unwrap_or(0xFF),
+            };
+            (*actor_event_flow_mgr).result_from_previous_check =
+                flag::check_zoneflag(roomid, event_flow_element.param1).into();
+        },
+        // Set, unset or check the stage object flag for object (param1) in
+        // scene (param2). Uses the current scene if param2 is 0xFFFF, other
+        // scenes are skipped unless they're the one that's loaded
+        80..=82 => unsafe {
+            let param2 = event_flow_element.param2;
+            let sceneindex = match param2 {
+                0xFFFF => flag::get_current_sceneindex(),
+                sceneindex if sceneindex <= flag::MAX_SCENEINDEX.into() => sceneindex as u8,
+                sceneindex => {
+                    debug::debug_print_num(
+                        cstr!("Skipping stage object flag with invalid scene: %d").as_ptr(),
+                        sceneindex as usize,
+                    );
+                    return;
+                },
+            };
+            let objectid = event_flow_element.param1;
+
+            match event_flow_element.param3 {
+                80 => flag::set_stage_object_flag(sceneindex, objectid),
+                81 => flag::unset_stage_object_flag(sceneindex, objectid),
+                _ => {
+                    (*actor_event_flow_mgr).result_from_previous_check =
+                        flag::check_stage_object_flag(sceneindex, objectid).into();
+                },
+            }
+        },
+        // Set the location flag (param1), used by NPCs giving randomized items
+        83 => flag::set_location_flag(event_flow_element.param1),
+        // Check location flag (param1), result is 1 if it's set
+        84 => unsafe {
+            (*actor_event_flow_mgr).result_from_previous_check =
+                flag::check_location_flag(event_flow_element.param1).into();
+        },
+        // Record which location the next give item command is for from the
+        // event file number (param1) and flow index (param2) of the command
+        85 => tracker::set_event_location(event_flow_element.param1, event_flow_element.param2),
         _ => (),
     }
 
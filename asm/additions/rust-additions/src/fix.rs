@@ -36,7 +36,6 @@ extern "C" {
     static LOFTWING_PTR: *mut player::dBird;
 
     static STORYFLAG_MGR: *mut flag::FlagMgr;
-    static SCENEFLAG_MGR: *mut flag::SceneflagMgr;
 
     static mut CURRENT_STAGE_NAME: [u8; 8];
 
@@ -282,11 +281,7 @@ pub fn check_for_botg_itemflag_for_light_tower(param1: u64, param2: u64, param3:
 
 #[no_mangle]
 pub fn set_skyloft_thunderhead_sceneflag() {
-    if unsafe { (*SCENEFLAG_MGR).sceneindex } == 0 {
-        flag::set_local_sceneflag(29);
-    }
-
-    flag::set_global_sceneflag(0, 29);
+    flag::FlagRef::sceneflag(0, 29).set();
 }
 
 // prevent_pyrup_fire_when_underground1
@@ -4,6 +4,9 @@
 
 use crate::actor;
 use crate::debug;
+use crate::entrance;
+use crate::item;
+use crate::observer;
 use crate::savefile;
 
 use core::arch::asm;
@@ -89,6 +92,36 @@ pub struct FlagMgrFuncs {
 }
 assert_eq_size!([u8; 112], FlagMgrFuncs);
 
+// Which flags a FlagRef refers to. Matches ER_FLAG_TYPE in entrance.rs for
+// the values they share so tables can move over to FlagRef
+#[repr(u8)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum FLAG_SPACE {
+    NONE              = 0,
+    STORYFLAG         = 1,
+    SCENEFLAG         = 2,
+    ITEMFLAG          = 3,
+    DUNGEONFLAG       = 4,
+    TEMPFLAG          = 5,
+    ZONEFLAG          = 6,
+    STAGE_OBJECT_FLAG = 7,
+    LOCATION_FLAG     = 8,
+}
+
+// A single flag in any flag space. `sceneindex` is only used by sceneflags and
+// dungeonflags, whether the live (local) flags or the save (global) flags are
+// used is decided from the current scene. Zoneflags use it for the roomid
+// instead as they always belong to the current scene. Stage object flags use
+// it for the scene the object is in
+#[repr(C, packed(1))]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct FlagRef {
+    pub space:      FLAG_SPACE,
+    pub sceneindex: u8,
+    pub flag:       u16,
+}
+assert_eq_size!(u32, FlagRef);
+
 // Flag enums
 #[repr(u16)]
 #[derive(Copy, Clone, Hash, PartialEq, Eq)]
@@ -305,14 +338,290 @@ pub enum ITEMFLAGS {
     MAX511                           = 0x1FF,
 }
 
+// Dungeonflag counter slots
+pub const DUNGEONFLAG_SMALL_KEY_COUNTER: usize = 1;
+
 // Start flag stuff
+// The startflags are a header followed by tagged records:
+//   record: tag (u8), length of the entries in bytes (u8), entries
+// See util/startflags.py for the packer and the layout of each tag's entries
+pub const STARTFLAGS_MAGIC: u32 = 0x4C465453; // "STFL" in memory
+pub const STARTFLAGS_VERSION: u16 = 4;
+pub const MAX_STARTFLAGS_SIZE: usize = 0x800;
+
 #[repr(C, packed(1))]
 #[derive(Copy, Clone)]
-pub struct StartCount {
-    pub counter: u16,
-    pub value:   u16,
+pub struct Startflags {
+    pub magic:   u32,
+    pub version: u16,
+    pub length:  u16,
+    pub records: [u8; MAX_STARTFLAGS_SIZE - 8],
+}
+assert_eq_size!([u8; MAX_STARTFLAGS_SIZE], Startflags);
+
+#[repr(u8)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum STARTFLAG_TAG {
+    STORYFLAG         = 1,
+    SCENEFLAG         = 2,
+    ITEMFLAG          = 3,
+    DUNGEONFLAG       = 4,
+    TEMPFLAG          = 5,
+    ZONEFLAG          = 6,
+    COUNTER           = 7,
+    LOADOUT_ITEM      = 8,
+    STAGE_OBJECT_FLAG = 9,
+}
+
+// Where a LOADOUT_ITEM startflag is stored
+#[repr(u8)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum LOADOUT_STORAGE {
+    POUCH      = 0,
+    ITEM_CHECK = 1,
+}
+
+pub const POUCH_SLOT_COUNT: usize = 8;
+pub const ITEM_CHECK_SLOT_COUNT: usize = 60;
+
+// Wooden Shield to Hylian Shield
+pub fn is_shield(itemid: u16) -> bool {
+    return itemid >= ITEMFLAGS::WOODEN_SHIELD as u16 && itemid <= ITEMFLAGS::HYLIAN_SHIELD as u16;
+}
+
+impl STARTFLAG_TAG {
+    // Tags come from the patcher so aren't trusted to be valid
+    pub fn from_u8(tag: u8) -> Option<STARTFLAG_TAG> {
+        match tag {
+            1 => return Some(STARTFLAG_TAG::STORYFLAG),
+            2 => return Some(STARTFLAG_TAG::SCENEFLAG),
+            3 => return Some(STARTFLAG_TAG::ITEMFLAG),
+            4 => return Some(STARTFLAG_TAG::DUNGEONFLAG),
+            5 => return Some(STARTFLAG_TAG::TEMPFLAG),
+            6 => return Some(STARTFLAG_TAG::ZONEFLAG),
+            7 => return Some(STARTFLAG_TAG::COUNTER),
+            8 => return Some(STARTFLAG_TAG::LOADOUT_ITEM),
+            9 => return Some(STARTFLAG_TAG::STAGE_OBJECT_FLAG),
+            _ => return None,
+        }
+    }
+
+    pub fn entry_size(&self) -> usize {
+        match self {
+            STARTFLAG_TAG::COUNTER => return 4,
+            STARTFLAG_TAG::LOADOUT_ITEM => return 6,
+            STARTFLAG_TAG::STAGE_OBJECT_FLAG => return 10,
+            _ => return 2,
+        }
+    }
+}
+
+impl FlagRef {
+    pub const fn storyflag(flag: u16) -> FlagRef {
+        return FlagRef {
+            space: FLAG_SPACE::STORYFLAG,
+            sceneindex: 0,
+            flag,
+        };
+    }
+
+    pub const fn sceneflag(sceneindex: u8, flag: u16) -> FlagRef {
+        return FlagRef {
+            space: FLAG_SPACE::SCENEFLAG,
+            sceneindex,
+            flag,
+        };
+    }
+
+    pub const fn itemflag(flag: ITEMFLAGS) -> FlagRef {
+        return FlagRef {
+            space:      FLAG_SPACE::ITEMFLAG,
+            sceneindex: 0,
+            flag:       flag as u16,
+        };
+    }
+
+    pub const fn dungeonflag(sceneindex: u8, flag: u16) -> FlagRef {
+        return FlagRef {
+            space: FLAG_SPACE::DUNGEONFLAG,
+            sceneindex,
+            flag,
+        };
+    }
+
+    pub const fn tempflag(flag: u16) -> FlagRef {
+        return FlagRef {
+            space: FLAG_SPACE::TEMPFLAG,
+            sceneindex: 0,
+            flag,
+        };
+    }
+
+    pub const fn zoneflag(roomid: u8, flag: u16) -> FlagRef {
+        return FlagRef {
+            space: FLAG_SPACE::ZONEFLAG,
+            sceneindex: roomid,
+            flag,
+        };
+    }
+
+    pub const fn location_flag(id: u16) -> FlagRef {
+        return FlagRef {
+            space:      FLAG_SPACE::LOCATION_FLAG,
+            sceneindex: 0,
+            flag:       id,
+        };
+    }
+
+    pub const fn stage_object_flag(sceneindex: u8, objectid: u16) -> FlagRef {
+        return FlagRef {
+            space: FLAG_SPACE::STAGE_OBJECT_FLAG,
+            sceneindex,
+            flag: objectid,
+        };
+    }
+
+    // True if the flag belongs to the scene that's currently loaded, so the
+    // live flags need updating as well as the save
+    pub fn is_local(&self) -> bool {
+        unsafe {
+            match self.space {
+                FLAG_SPACE::SCENEFLAG => {
+                    return (*SCENEFLAG_MGR).sceneindex == self.sceneindex as u16;
+                },
+                FLAG_SPACE::DUNGEONFLAG => return is_local_dungeon(self.sceneindex.into()),
+                _ => return false,
+            }
+        }
+    }
+
+    pub fn check(&self) -> bool {
+        return self.get_counter() != 0;
+    }
+
+    // Setting, unsetting and changing counters all notify the flag observers
+    // if the flag changes, see observer.rs
+    pub fn set(&self) {
+        let flag = self.flag;
+        let was_set = self.check();
+
+        match self.space {
+            FLAG_SPACE::STORYFLAG => unsafe {
+                ((*(*STORYFLAG_MGR).funcs).set_flag)(STORYFLAG_MGR, flag);
+            },
+            FLAG_SPACE::ITEMFLAG => unsafe {
+                ((*(*ITEMFLAG_MGR).funcs).set_flag)(ITEMFLAG_MGR, flag);
+            },
+            FLAG_SPACE::SCENEFLAG => {
+                if self.is_local() {
+                    unsafe { SceneflagMgr__setFlag(SCENEFLAG_MGR, 0, flag.into()) };
+                }
+                set_global_sceneflag(self.sceneindex.into(), flag);
+            },
+            FLAG_SPACE::DUNGEONFLAG => set_dungeonflag(self.sceneindex.into(), flag),
+            FLAG_SPACE::TEMPFLAG => set_tempflag(flag),
+            FLAG_SPACE::ZONEFLAG => set_zoneflag(self.sceneindex, flag),
+            FLAG_SPACE::STAGE_OBJECT_FLAG => set_stage_object_flag(self.sceneindex, flag),
+            FLAG_SPACE::LOCATION_FLAG => set_location_flag(flag),
+            FLAG_SPACE::NONE => {},
+        }
+
+        observer::notify_flag_change(*self, was_set, true);
+    }
+
+    pub fn unset(&self) {
+        let flag = self.flag;
+        let was_set = self.check();
+
+        match self.space {
+            FLAG_SPACE::STORYFLAG => unsafe {
+                ((*(*STORYFLAG_MGR).funcs).unset_flag)(STORYFLAG_MGR, flag);
+            },
+            FLAG_SPACE::ITEMFLAG => unsafe {
+                ((*(*ITEMFLAG_MGR).funcs).unset_flag)(ITEMFLAG_MGR, flag);
+            },
+            FLAG_SPACE::SCENEFLAG => {
+                if self.is_local() {
+                    unsafe { SceneflagMgr__unsetFlag(SCENEFLAG_MGR, 0, flag.into()) };
+                }
+                unset_global_sceneflag(self.sceneindex.into(), flag);
+            },
+            FLAG_SPACE::DUNGEONFLAG => unset_dungeonflag(self.sceneindex.into(), flag),
+            FLAG_SPACE::TEMPFLAG => unset_tempflag(flag),
+            FLAG_SPACE::ZONEFLAG => unset_zoneflag(self.sceneindex, flag),
+            FLAG_SPACE::STAGE_OBJECT_FLAG => unset_stage_object_flag(self.sceneindex, flag),
+            FLAG_SPACE::LOCATION_FLAG => unset_location_flag(flag),
+            FLAG_SPACE::NONE => {},
+        }
+
+        observer::notify_flag_change(*self, was_set, false);
+    }
+
+    // Storyflags and itemflags can be counters, other flags are 0 or 1
+    pub fn get_counter(&self) -> u16 {
+        let flag = self.flag;
+
+        unsafe {
+            match self.space {
+                FLAG_SPACE::STORYFLAG => return check_storyflag(flag) as u16,
+                FLAG_SPACE::ITEMFLAG => {
+                    return ((*(*ITEMFLAG_MGR).funcs).get_flag_or_counter)(ITEMFLAG_MGR, flag)
+                        as u16;
+                },
+                FLAG_SPACE::SCENEFLAG => {
+                    if self.is_local() {
+                        return check_local_sceneflag(flag.into());
+                    }
+                    return check_global_sceneflag(self.sceneindex.into(), flag);
+                },
+                FLAG_SPACE::DUNGEONFLAG => {
+                    return check_dungeonflag(self.sceneindex.into(), flag);
+                },
+                FLAG_SPACE::TEMPFLAG => return check_tempflag(flag),
+                FLAG_SPACE::ZONEFLAG => return check_zoneflag(self.sceneindex, flag),
+                FLAG_SPACE::STAGE_OBJECT_FLAG => {
+                    return check_stage_object_flag(self.sceneindex, flag);
+                },
+                FLAG_SPACE::LOCATION_FLAG => return check_location_flag(flag),
+                FLAG_SPACE::NONE => return 0,
+            }
+        }
+    }
+
+    pub fn set_counter(&self, value: u16) {
+        let flag = self.flag;
+
+        unsafe {
+            match self.space {
+                FLAG_SPACE::STORYFLAG => {
+                    let was_set = self.check();
+                    ((*(*STORYFLAG_MGR).funcs).set_flag_or_counter_to_value)(
+                        STORYFLAG_MGR,
+                        flag,
+                        value,
+                    );
+                    observer::notify_flag_change(*self, was_set, value != 0);
+                },
+                FLAG_SPACE::ITEMFLAG => {
+                    let was_set = self.check();
+                    ((*(*ITEMFLAG_MGR).funcs).set_flag_or_counter_to_value)(
+                        ITEMFLAG_MGR,
+                        flag,
+                        value,
+                    );
+                    observer::notify_flag_change(*self, was_set, value != 0);
+                },
+                _ => {
+                    if value != 0 {
+                        self.set();
+                    } else {
+                        self.unset();
+                    }
+                },
+            }
+        }
+    }
 }
-assert_eq_size!(u32, StartCount);
 
 // IMPORTANT: when using vanilla code, the start point must be declared in
 // symbols.yaml and then added to this extern block.
@@ -331,12 +640,12 @@ extern "C" {
     static mut STATIC_ITEMFLAGS: [u16; 64];
     static mut STATIC_DUNGEONFLAGS: [u16; 8];
 
+    static CURRENT_ROOM: u8;
     static mut NEXT_NIGHT: u8;
     static mut NEXT_UNK: u8;
 
     // Custom symbols
-    static STARTFLAGS: [u16; 1000];
-    static START_COUNTS: [StartCount; 50];
+    static STARTFLAGS: Startflags;
 
     static mut TRIAL_GATE_EXIT_WAIT_TIMER: u32;
 
@@ -362,16 +671,12 @@ extern "C" {
 // Storyflags
 #[no_mangle]
 pub fn set_storyflag(flag: u16) {
-    unsafe {
-        ((*(*STORYFLAG_MGR).funcs).set_flag)(STORYFLAG_MGR, flag);
-    };
+    FlagRef::storyflag(flag).set();
 }
 
 #[no_mangle]
 pub fn unset_storyflag(flag: u16) {
-    unsafe {
-        ((*(*STORYFLAG_MGR).funcs).unset_flag)(STORYFLAG_MGR, flag);
-    };
+    FlagRef::storyflag(flag).unset();
 }
 
 #[no_mangle]
@@ -382,18 +687,30 @@ pub fn check_storyflag(flag: u16) -> u32 {
 }
 
 // Sceneflags (local)
+// These only change the live flags, the observers are told about the current
+// scene's flag
 #[no_mangle]
 pub fn set_local_sceneflag(flag: u32) {
+    let was_set = check_local_sceneflag(flag) != 0;
+
     unsafe {
-        return SceneflagMgr__setFlag(SCENEFLAG_MGR, 0, flag);
+        SceneflagMgr__setFlag(SCENEFLAG_MGR, 0, flag);
     }
+
+    let flag_ref = FlagRef::sceneflag(get_current_sceneindex(), flag as u16);
+    observer::notify_flag_change(flag_ref, was_set, true);
 }
 
 #[no_mangle]
 pub fn unset_local_sceneflag(flag: u32) {
+    let was_set = check_local_sceneflag(flag) != 0;
+
     unsafe {
-        return SceneflagMgr__unsetFlag(SCENEFLAG_MGR, 0, flag);
+        SceneflagMgr__unsetFlag(SCENEFLAG_MGR, 0, flag);
     }
+
+    let flag_ref = FlagRef::sceneflag(get_current_sceneindex(), flag as u16);
+    observer::notify_flag_change(flag_ref, was_set, false);
 }
 
 #[no_mangle]
@@ -403,83 +720,480 @@ pub fn check_local_sceneflag(flag: u32) -> u16 {
     }
 }
 
+// Flag bounds
+// Scenes, rooms and flags mostly come from patcher data, so they're checked
+// before the flag arrays are indexed. Anything out of range is reported and
+// the access is skipped instead of writing over whatever follows the flags
+pub const MAX_SCENEINDEX: u8 = 25;
+pub const MAX_ROOMID: u8 = 62;
+pub const FLAGS_PER_SCENE: u16 = 128;
+pub const FLAGS_PER_ROOM: u16 = 64;
+pub const TEMPFLAG_COUNT: u16 = 64;
+pub const ITEMFLAG_COUNT: u16 = ITEMFLAGS::MAX511 as u16 + 1;
+
+// A flag that's been checked, `word` and `mask` pick out its bit
+pub struct FlagPosition {
+    pub index: usize,
+    pub word:  usize,
+    pub mask:  u16,
+}
+
+impl FlagPosition {
+    fn new(index: u16, flag: u16) -> FlagPosition {
+        return FlagPosition {
+            index: index as usize,
+            word:  (flag >> 4) as usize,
+            mask:  1 << (flag & 0x0F),
+        };
+    }
+}
+
+// `index` is the scene or room, `message` needs two %d for it and the flag
+pub fn get_flag_position(
+    message: *const c_char,
+    index: u16,
+    max_index: u16,
+    flag: u16,
+    flag_count: u16,
+) -> Option<FlagPosition> {
+    if index > max_index || flag >= flag_count {
+        debug::debug_print_nums(message, index as usize, flag as usize);
+        return None;
+    }
+
+    return Some(FlagPosition::new(index, flag));
+}
+
+fn get_sceneflag_position(sceneindex: u16, flag: u16) -> Option<FlagPosition> {
+    return get_flag_position(
+        cstr!("Out of range sceneflag (scene %d, flag %d)").as_ptr(),
+        sceneindex,
+        MAX_SCENEINDEX.into(),
+        flag,
+        FLAGS_PER_SCENE,
+    );
+}
+
+fn get_dungeonflag_position(sceneindex: u16, flag: u16) -> Option<FlagPosition> {
+    return get_flag_position(
+        cstr!("Out of range dungeonflag (scene %d, flag %d)").as_ptr(),
+        sceneindex,
+        MAX_SCENEINDEX.into(),
+        flag,
+        FLAGS_PER_SCENE,
+    );
+}
+
 // Sceneflags (global)
 #[no_mangle]
 pub fn set_global_sceneflag(sceneindex: u16, flag: u16) {
-    let upper_flag = (flag & 0xF0) >> 4;
-    let lower_flag = flag & 0x0F;
-
-    unsafe {
-        (*FILE_MGR).FA.sceneflags[sceneindex as usize][upper_flag as usize] |= 1 << lower_flag;
+    if let Some(pos) = get_sceneflag_position(sceneindex, flag) {
+        unsafe {
+            (*FILE_MGR).FA.sceneflags[pos.index][pos.word] |= pos.mask;
+        }
     }
 }
 
 #[no_mangle]
 pub fn unset_global_sceneflag(sceneindex: u16, flag: u16) {
-    let upper_flag = (flag & 0xF0) >> 4;
-    let lower_flag = flag & 0x0F;
-
-    unsafe {
-        (*FILE_MGR).FA.sceneflags[sceneindex as usize][upper_flag as usize] &= !(1 << lower_flag);
+    if let Some(pos) = get_sceneflag_position(sceneindex, flag) {
+        unsafe {
+            (*FILE_MGR).FA.sceneflags[pos.index][pos.word] &= !pos.mask;
+        }
     }
 }
 
 #[no_mangle]
 pub fn check_global_sceneflag(sceneindex: u16, flag: u16) -> u16 {
-    let upper_flag = (flag & 0xF0) >> 4;
-    let lower_flag = flag & 0x0F;
+    match get_sceneflag_position(sceneindex, flag) {
+        Some(pos) => unsafe {
+            return ((*FILE_MGR).FA.sceneflags[pos.index][pos.word] & pos.mask != 0).into();
+        },
+        None => return 0,
+    }
+}
 
-    unsafe {
-        return ((*FILE_MGR).FA.sceneflags[sceneindex as usize][upper_flag as usize] >> lower_flag)
-            & 0x1;
+// Dungeonflags (local)
+// The live flags of the dungeon that's currently loaded
+#[no_mangle]
+pub fn set_local_dungeonflag(flag: u16) {
+    if let Some(pos) = get_dungeonflag_position(0, flag) {
+        unsafe {
+            STATIC_DUNGEONFLAGS[pos.word] |= pos.mask;
+        }
+    }
+}
+
+#[no_mangle]
+pub fn unset_local_dungeonflag(flag: u16) {
+    if let Some(pos) = get_dungeonflag_position(0, flag) {
+        unsafe {
+            STATIC_DUNGEONFLAGS[pos.word] &= !pos.mask;
+        }
+    }
+}
+
+#[no_mangle]
+pub fn check_local_dungeonflag(flag: u16) -> u16 {
+    match get_dungeonflag_position(0, flag) {
+        Some(pos) => unsafe {
+            return (STATIC_DUNGEONFLAGS[pos.word] & pos.mask != 0).into();
+        },
+        None => return 0,
     }
 }
 
 // Dungeonflags (global)
 #[no_mangle]
 pub fn set_global_dungeonflag(sceneindex: u16, flag: u16) {
-    let upper_flag = (flag & 0xF0) >> 4;
-    let lower_flag = flag & 0x0F;
+    if let Some(pos) = get_dungeonflag_position(sceneindex, flag) {
+        unsafe {
+            (*FILE_MGR).FA.dungeonflags[pos.index][pos.word] |= pos.mask;
+        }
+    }
+}
 
-    unsafe {
-        (*FILE_MGR).FA.dungeonflags[sceneindex as usize][upper_flag as usize] |= 1 << lower_flag;
+#[no_mangle]
+pub fn unset_global_dungeonflag(sceneindex: u16, flag: u16) {
+    if let Some(pos) = get_dungeonflag_position(sceneindex, flag) {
+        unsafe {
+            (*FILE_MGR).FA.dungeonflags[pos.index][pos.word] &= !pos.mask;
+        }
     }
 }
 
 #[no_mangle]
 pub fn check_global_dungeonflag(sceneindex: u16, flag: u16) -> u16 {
-    let upper_flag = (flag & 0xF0) >> 4;
-    let lower_flag = flag & 0x0F;
+    match get_dungeonflag_position(sceneindex, flag) {
+        Some(pos) => unsafe {
+            return ((*FILE_MGR).FA.dungeonflags[pos.index][pos.word] & pos.mask != 0).into();
+        },
+        None => return 0,
+    }
+}
 
+// Dungeonflags (both)
+// If the dungeon is loaded, the live flags and the save copy are written
+// together so they can't drift apart before the next commit
+pub fn is_local_dungeon(sceneindex: u16) -> bool {
     unsafe {
-        return ((*FILE_MGR).FA.dungeonflags[sceneindex as usize][upper_flag as usize]
-            >> lower_flag)
-            & 0x1;
+        return !DUNGEONFLAG_MGR.is_null() && (*DUNGEONFLAG_MGR).sceneindex as u16 == sceneindex;
     }
 }
 
-// Itemflags
 #[no_mangle]
-pub fn set_itemflag(flag: ITEMFLAGS) {
+pub fn set_dungeonflag(sceneindex: u16, flag: u16) {
+    if is_local_dungeon(sceneindex) {
+        set_local_dungeonflag(flag);
+    }
+    set_global_dungeonflag(sceneindex, flag);
+}
+
+#[no_mangle]
+pub fn unset_dungeonflag(sceneindex: u16, flag: u16) {
+    if is_local_dungeon(sceneindex) {
+        unset_local_dungeonflag(flag);
+    }
+    unset_global_dungeonflag(sceneindex, flag);
+}
+
+#[no_mangle]
+pub fn check_dungeonflag(sceneindex: u16, flag: u16) -> u16 {
+    if is_local_dungeon(sceneindex) {
+        return check_local_dungeonflag(flag);
+    }
+    return check_global_dungeonflag(sceneindex, flag);
+}
+
+// Dungeonflag counters
+// Some of the 16 bit slots hold a count instead of flags. The save copy is
+// only updated when the dungeonflags are committed, so read the live flags
+// while the dungeon is loaded
+pub const DUNGEONFLAG_SLOT_COUNT: usize = 8;
+
+fn check_dungeonflag_counter(sceneindex: u16, slot: usize) -> bool {
+    if sceneindex > MAX_SCENEINDEX.into() || slot >= DUNGEONFLAG_SLOT_COUNT {
+        debug::debug_print_nums(
+            cstr!("Out of range dungeonflag counter: scene %d, slot %d").as_ptr(),
+            sceneindex as usize,
+            slot,
+        );
+        return false;
+    }
+    return true;
+}
+
+#[no_mangle]
+pub fn get_dungeonflag_counter(sceneindex: u16, slot: usize) -> u16 {
+    if !check_dungeonflag_counter(sceneindex, slot) {
+        return 0;
+    }
+
     unsafe {
-        ((*(*ITEMFLAG_MGR).funcs).set_flag)(ITEMFLAG_MGR, flag as u16);
+        if is_local_dungeon(sceneindex) {
+            return STATIC_DUNGEONFLAGS[slot];
+        }
+        return (*FILE_MGR).FA.dungeonflags[sceneindex as usize][slot];
     }
 }
 
 #[no_mangle]
-pub fn set_itemflag_or_counter_to_value(flag: ITEMFLAGS, value: u16) {
+pub fn set_dungeonflag_counter(sceneindex: u16, slot: usize, value: u16) {
+    if !check_dungeonflag_counter(sceneindex, slot) {
+        return;
+    }
+
     unsafe {
-        ((*(*ITEMFLAG_MGR).funcs).set_flag_or_counter_to_value)(ITEMFLAG_MGR, flag as u16, value);
+        if is_local_dungeon(sceneindex) {
+            STATIC_DUNGEONFLAGS[slot] = value;
+        }
+        (*FILE_MGR).FA.dungeonflags[sceneindex as usize][slot] = value;
     }
 }
 
+// Clamps at 0 so removing a key that isn't there doesn't wrap around
 #[no_mangle]
-pub fn unset_itemflag(flag: ITEMFLAGS) {
+pub fn add_to_dungeonflag_counter(sceneindex: u16, slot: usize, amount: i16) {
+    let value = get_dungeonflag_counter(sceneindex, slot).saturating_add_signed(amount);
+    set_dungeonflag_counter(sceneindex, slot, value);
+}
+
+// Tempflags
+// These only last until the next scene is loaded. The live flags and the save
+// copy are both written so the change sticks whether or not the scene is
+// committed first
+fn get_tempflag_position(flag: u16) -> Option<FlagPosition> {
+    if flag >= TEMPFLAG_COUNT {
+        debug::debug_print_num(cstr!("Out of range tempflag: %d").as_ptr(), flag as usize);
+        return None;
+    }
+    return Some(FlagPosition::new(0, flag));
+}
+
+#[no_mangle]
+pub fn set_tempflag(flag: u16) {
+    if let Some(pos) = get_tempflag_position(flag) {
+        unsafe {
+            STATIC_TEMPFLAGS[pos.word] |= pos.mask;
+            (*FILE_MGR).FA.tempflags[pos.word] |= pos.mask;
+        }
+    }
+}
+
+#[no_mangle]
+pub fn unset_tempflag(flag: u16) {
+    if let Some(pos) = get_tempflag_position(flag) {
+        unsafe {
+            STATIC_TEMPFLAGS[pos.word] &= !pos.mask;
+            (*FILE_MGR).FA.tempflags[pos.word] &= !pos.mask;
+        }
+    }
+}
+
+#[no_mangle]
+pub fn check_tempflag(flag: u16) -> u16 {
+    match get_tempflag_position(flag) {
+        Some(pos) => unsafe {
+            return (STATIC_TEMPFLAGS[pos.word] & pos.mask != 0).into();
+        },
+        None => return 0,
+    }
+}
+
+// Zoneflags
+// Like tempflags but per room of the current scene
+fn get_zoneflag_position(roomid: u8, flag: u16) -> Option<FlagPosition> {
+    return get_flag_position(
+        cstr!("Out of range zoneflag (room %d, flag %d)").as_ptr(),
+        roomid.into(),
+        MAX_ROOMID.into(),
+        flag,
+        FLAGS_PER_ROOM,
+    );
+}
+
+#[no_mangle]
+pub fn set_zoneflag(roomid: u8, flag: u16) {
+    if let Some(pos) = get_zoneflag_position(roomid, flag) {
+        unsafe {
+            STATIC_ZONEFLAGS[pos.index][pos.word] |= pos.mask;
+            (*FILE_MGR).FA.zoneflags[pos.index][pos.word] |= pos.mask;
+        }
+    }
+}
+
+#[no_mangle]
+pub fn unset_zoneflag(roomid: u8, flag: u16) {
+    if let Some(pos) = get_zoneflag_position(roomid, flag) {
+        unsafe {
+            STATIC_ZONEFLAGS[pos.index][pos.word] &= !pos.mask;
+            (*FILE_MGR).FA.zoneflags[pos.index][pos.word] &= !pos.mask;
+        }
+    }
+}
+
+#[no_mangle]
+pub fn check_zoneflag(roomid: u8, flag: u16) -> u16 {
+    match get_zoneflag_position(roomid, flag) {
+        Some(pos) => unsafe {
+            return (STATIC_ZONEFLAGS[pos.index][pos.word] & pos.mask != 0).into();
+        },
+        None => return 0,
+    }
+}
+
+// The room the player was spawned into
+pub fn get_current_room() -> u8 {
     unsafe {
-        ((*(*ITEMFLAG_MGR).funcs).unset_flag)(ITEMFLAG_MGR, flag as u16);
+        return CURRENT_ROOM;
+    }
+}
+
+// Stage object flags
+// Remember which objects (bombable walls, doors, bars, etc.) in the loaded
+// stage have been broken or opened. There's a bit for every object id the
+// stage files can give an object: the room in the top 6 bits and the object's
+// index in the room in the bottom 10 (see object_add in
+// patches/stagepatchhandler.py). That's 0x10000 bits, which is exactly the
+// size of the save array, so the object id is used as the flag directly. The
+// ids only mean anything in their own stage, so only the loaded scene's flags
+// can be changed. Objects only read their flag when they're loaded, so
+// changing the flag of an object that's already loaded takes effect the next
+// time it loads
+fn get_stage_object_flag_position(sceneindex: u8, objectid: u16) -> Option<FlagPosition> {
+    if sceneindex > MAX_SCENEINDEX {
+        debug::debug_print_nums(
+            cstr!("Out of range stage object flag: scene %d, object %d").as_ptr(),
+            sceneindex as usize,
+            objectid as usize,
+        );
+        return None;
+    }
+
+    if sceneindex != get_current_sceneindex() {
+        debug::debug_print_nums(
+            cstr!("Stage object flag for a scene that isn't loaded: scene %d, object %d").as_ptr(),
+            sceneindex as usize,
+            objectid as usize,
+        );
+        return None;
+    }
+
+    return Some(FlagPosition::new(0, objectid));
+}
+
+#[no_mangle]
+pub fn set_stage_object_flag(sceneindex: u8, objectid: u16) {
+    if let Some(pos) = get_stage_object_flag_position(sceneindex, objectid) {
+        unsafe {
+            (*FILE_MGR).FA.stage_object_flags[pos.word] |= pos.mask;
+        }
     }
 }
 
+#[no_mangle]
+pub fn unset_stage_object_flag(sceneindex: u8, objectid: u16) {
+    if let Some(pos) = get_stage_object_flag_position(sceneindex, objectid) {
+        unsafe {
+            (*FILE_MGR).FA.stage_object_flags[pos.word] &= !pos.mask;
+        }
+    }
+}
+
+#[no_mangle]
+pub fn check_stage_object_flag(sceneindex: u8, objectid: u16) -> u16 {
+    match get_stage_object_flag_position(sceneindex, objectid) {
+        Some(pos) => unsafe {
+            return ((*FILE_MGR).FA.stage_object_flags[pos.word] & pos.mask != 0).into();
+        },
+        None => return 0,
+    }
+}
+
+// The scene that's currently loaded
+pub fn get_current_sceneindex() -> u8 {
+    unsafe {
+        return (*SCENEFLAG_MGR).sceneindex as u8;
+    }
+}
+
+// Location flags
+// Randomizer owned flags for locations that don't have a vanilla flag of their
+// own, stored in RandoSaveData. A location flag id is the index of the flag.
+// Item actors and tgreacts only have 10 bits of param2 free so they can only
+// use ids below ACTOR_LOCATION_FLAG_NONE, anything with a full u16 (event
+// flows, FlagRefs) can use any of them
+pub const LOCATION_FLAG_COUNT: usize = 4096;
+pub const LOCATION_FLAG_WORDS: usize = LOCATION_FLAG_COUNT / 16;
+pub const LOCATION_FLAG_NONE: u16 = 0xFFFF;
+pub const ACTOR_LOCATION_FLAG_NONE: u16 = 0x3FF;
+
+// Reads the location flag id from bits 8-17 of an item actor's or tgreact's
+// param2
+pub fn get_actor_location_flag(param2: u32) -> u16 {
+    let id = ((param2 >> 8) & ACTOR_LOCATION_FLAG_NONE as u32) as u16;
+    if id == ACTOR_LOCATION_FLAG_NONE {
+        return LOCATION_FLAG_NONE;
+    }
+    return id;
+}
+
+fn get_location_flag_position(id: u16) -> Option<FlagPosition> {
+    if id as usize >= LOCATION_FLAG_COUNT {
+        debug::debug_print_num(
+            cstr!("Out of range location flag: %d").as_ptr(),
+            id as usize,
+        );
+        return None;
+    }
+    return Some(FlagPosition::new(0, id));
+}
+
+#[no_mangle]
+pub fn set_location_flag(id: u16) {
+    if let Some(pos) = get_location_flag_position(id) {
+        if let Some(rando) = savefile::get_rando_save_data() {
+            rando.location_flags[pos.word] |= pos.mask;
+        }
+    }
+}
+
+#[no_mangle]
+pub fn unset_location_flag(id: u16) {
+    if let Some(pos) = get_location_flag_position(id) {
+        if let Some(rando) = savefile::get_rando_save_data() {
+            rando.location_flags[pos.word] &= !pos.mask;
+        }
+    }
+}
+
+#[no_mangle]
+pub fn check_location_flag(id: u16) -> u16 {
+    match (
+        get_location_flag_position(id),
+        savefile::get_rando_save_data(),
+    ) {
+        (Some(pos), Some(rando)) => return (rando.location_flags[pos.word] & pos.mask != 0).into(),
+        _ => return 0,
+    }
+}
+
+// Itemflags
+#[no_mangle]
+pub fn set_itemflag(flag: ITEMFLAGS) {
+    FlagRef::itemflag(flag).set();
+}
+
+#[no_mangle]
+pub fn set_itemflag_or_counter_to_value(flag: ITEMFLAGS, value: u16) {
+    FlagRef::itemflag(flag).set_counter(value);
+}
+
+#[no_mangle]
+pub fn unset_itemflag(flag: ITEMFLAGS) {
+    FlagRef::itemflag(flag).unset();
+}
+
 #[no_mangle]
 pub fn check_itemflag(flag: ITEMFLAGS) -> u32 {
     unsafe {
@@ -494,9 +1208,15 @@ pub fn set_goddess_sword_pulled_story_flag() {
     set_storyflag(951);
 }
 
+// The day/night and Stone of Trials storyflags are set from their own patch
+// points as they follow a reload rather than another flag. They go through
+// FlagRef so the flag observers still hear about them
+pub const NIGHT_STORYFLAG: FlagRef = FlagRef::storyflag(899);
+pub const STONE_OF_TRIALS_PLACED_STORYFLAG: FlagRef = FlagRef::storyflag(22);
+
 #[no_mangle]
 pub fn check_night_storyflag() -> bool {
-    return check_storyflag(899) != 0; // 899 == day/night flag
+    return NIGHT_STORYFLAG.check();
 }
 
 #[no_mangle]
@@ -504,13 +1224,12 @@ pub fn update_day_night_storyflag() {
     // debug::debug_print("Updating night flag");
 
     unsafe {
-        // 899 == day/night storyflag
         if NEXT_NIGHT == 1 {
             // debug::debug_print("Setting night flag");
-            set_storyflag(899);
+            NIGHT_STORYFLAG.set();
         } else {
             // debug::debug_print("Unsetting night flag");
-            unset_storyflag(899);
+            NIGHT_STORYFLAG.unset();
         }
 
         ((*(*STORYFLAG_MGR).funcs).do_commit)(STORYFLAG_MGR);
@@ -540,7 +1259,7 @@ pub fn set_stone_of_trials_placed_flag(
         )
     }
 
-    set_storyflag(22); // 22 == Stone of Trials placed storyflag
+    STONE_OF_TRIALS_PLACED_STORYFLAG.set();
 }
 
 #[no_mangle]
@@ -575,112 +1294,59 @@ pub fn handle_startflags() {
     unsafe {
         (*FILE_MGR).prevent_commit = true;
 
-        let mut delimiter_count = 0;
-        let mut pouch_item_counter = 0;
-
-        for flag_ptr in STARTFLAGS.iter() {
-            let mut flag = *flag_ptr;
-
-            if flag == 0xFFFF {
-                delimiter_count += 1;
-                continue;
-            }
-
-            match delimiter_count {
-                // Storyflags
-                0 => {
-                    ((*(*STORYFLAG_MGR).funcs).set_flag)(STORYFLAG_MGR, flag.into());
-                },
-
-                // Sceneflags
-                1 => {
-                    // flag = 0xFFSS where SS == sceneindex and FF == sceneflag
-                    let sceneindex = flag & 0xFF;
-                    let sceneflag = flag >> 8;
-
-                    if (*SCENEFLAG_MGR).sceneindex == sceneindex {
-                        set_local_sceneflag(sceneflag.into());
-                    }
-
-                    set_global_sceneflag(sceneindex, sceneflag);
-                },
-
-                // Itemflags
-                2 => {
-                    ((*(*ITEMFLAG_MGR).funcs).set_flag)(ITEMFLAG_MGR, flag.into());
-
-                    // Set pouch items if applicable
-                    match flag {
-                        // Hylian Shield
-                        125 => {
-                            (*FILE_MGR).FA.pouch_items[pouch_item_counter] = 125 | 0x30 << 0x10;
-                            pouch_item_counter += 1;
-                        },
-                        // Bottle
-                        153 => {
-                            (*FILE_MGR).FA.pouch_items[pouch_item_counter] = 153;
-                            pouch_item_counter += 1;
-                        },
-                        _ => {},
-                    }
-                },
-
-                // Dungeonflags
-                3 => {
-                    let sceneindex = flag & 0xFF;
-                    flag = flag >> 8;
-
-                    // Convert dungeonflag numbers to be like sceneflags
-                    // Dungeonflags start offset by 1 due to an undefined value in the flag
-                    // definitions.
-                    if flag == 2 || flag == 3 || flag == 4 {
-                        flag -= 1;
-                    } else if flag == 12 {
-                        // The rooms are defined before the boss key placed flag
-                        flag = 7;
-                    } else if flag == 16 {
-                        flag = 8;
-                    }
-
-                    // flag = 0xFFSS where SS == sceneindex and FF == dungeonflag
-                    set_global_dungeonflag(sceneindex, flag);
-                },
+        let mut starting_hearts: u16 = 6 * 4;
+        let mut shield_equipped = false;
 
-                _ => {
+        if STARTFLAGS.magic != STARTFLAGS_MAGIC || STARTFLAGS.version != STARTFLAGS_VERSION {
+            debug::debug_print_num(
+                cstr!("Unknown startflags version: %d").as_ptr(),
+                STARTFLAGS.version as usize,
+            );
+        } else {
+            let records = &STARTFLAGS.records;
+            let length = (STARTFLAGS.length as usize).min(records.len());
+            let mut offset = 0;
+
+            while offset + 2 <= length {
+                let tag = records[offset];
+                let entries_start = offset + 2;
+                offset = entries_start + records[offset + 1] as usize;
+
+                if offset > length {
+                    debug::debug_print_num(
+                        cstr!("Startflag record overruns the startflags: %d").as_ptr(),
+                        tag as usize,
+                    );
                     break;
-                },
+                }
+
+                match STARTFLAG_TAG::from_u8(tag) {
+                    Some(tag) => {
+                        let entries = &records[entries_start..offset];
+                        if entries.len() % tag.entry_size() != 0 {
+                            debug::debug_print_num(
+                                cstr!("Startflag record has a partial entry: %d").as_ptr(),
+                                tag as usize,
+                            );
+                        }
+
+                        for entry in entries.chunks_exact(tag.entry_size()) {
+                            apply_startflag(tag, entry, &mut starting_hearts, &mut shield_equipped);
+                        }
+                    },
+                    None => {
+                        debug::debug_print_num(
+                            cstr!("Skipping unknown startflag tag: %d").as_ptr(),
+                            tag as usize,
+                        );
+                    },
+                }
             }
         }
 
         // Always set the empty bottle itemflag to allow buying potions from Luv
         ((*(*ITEMFLAG_MGR).funcs).set_flag)(ITEMFLAG_MGR, ITEMFLAGS::EMPTY_BOTTLE as u16);
 
-        let mut starting_hearts: u16 = 6 * 4;
-
-        for start_count in START_COUNTS.iter() {
-            if start_count.counter == 0xFFFF {
-                break;
-            }
-
-            // Total up Heart Pieces and Heart Containers for starting health
-            if start_count.counter == 0x5E {
-                starting_hearts += start_count.value;
-            } else if start_count.counter == 0x5D {
-                starting_hearts += start_count.value * 4;
-            }
-            // If the counter is less than 25, it's a dungeon scene
-            // for small key counts. Otherwise, it's a regular item flag counter
-            else if start_count.counter <= 25 {
-                (*FILE_MGR).FA.dungeonflags[start_count.counter as usize][1] = start_count.value;
-            } else {
-                ((*(*ITEMFLAG_MGR).funcs).set_flag_or_counter_to_value)(
-                    ITEMFLAG_MGR,
-                    start_count.counter,
-                    start_count.value,
-                );
-            }
-        }
-
         // Apply starting hearts
         (*FILE_MGR).FA.health_capacity = starting_hearts;
         (*FILE_MGR).FA.current_health = starting_hearts;
@@ -691,6 +1357,187 @@ pub fn handle_startflags() {
         ((*(*STORYFLAG_MGR).funcs).do_commit)(STORYFLAG_MGR);
         ((*(*ITEMFLAG_MGR).funcs).do_commit)(ITEMFLAG_MGR);
 
+        // The startflags are written straight to the flag managers and the
+        // save, so the flag observers never hear about them. Run whatever
+        // they'd have done for the starting items here instead, once the
+        // itemflags have been committed
+        item::open_trial_gates();
+
         (*FILE_MGR).prevent_commit = false;
     }
 }
+
+fn apply_startflag(
+    tag: STARTFLAG_TAG,
+    entry: &[u8],
+    starting_hearts: &mut u16,
+    shield_equipped: &mut bool,
+) {
+    let value = u16::from_le_bytes([entry[0], entry[1]]);
+
+    unsafe {
+        match tag {
+            STARTFLAG_TAG::STORYFLAG => {
+                ((*(*STORYFLAG_MGR).funcs).set_flag)(STORYFLAG_MGR, value);
+            },
+
+            // entry = sceneindex, sceneflag
+            STARTFLAG_TAG::SCENEFLAG => {
+                if entry[0] > MAX_SCENEINDEX {
+                    debug::debug_print_num(
+                        cstr!("Skipping startflag with invalid scene: %d").as_ptr(),
+                        entry[0] as usize,
+                    );
+                    return;
+                }
+
+                // Not FlagRef::set() as the observers aren't told about
+                // startflags, see handle_startflags
+                if FlagRef::sceneflag(entry[0], entry[1].into()).is_local() {
+                    SceneflagMgr__setFlag(SCENEFLAG_MGR, 0, entry[1].into());
+                }
+                set_global_sceneflag(entry[0].into(), entry[1].into());
+            },
+
+            STARTFLAG_TAG::ITEMFLAG => {
+                if value >= ITEMFLAG_COUNT {
+                    debug::debug_print_num(
+                        cstr!("Skipping startflag with invalid itemflag: %d").as_ptr(),
+                        value as usize,
+                    );
+                    return;
+                }
+
+                ((*(*ITEMFLAG_MGR).funcs).set_flag)(ITEMFLAG_MGR, value);
+            },
+
+            // entry = sceneindex, dungeonflag
+            STARTFLAG_TAG::DUNGEONFLAG => {
+                if entry[0] > MAX_SCENEINDEX {
+                    debug::debug_print_num(
+                        cstr!("Skipping startflag with invalid scene: %d").as_ptr(),
+                        entry[0] as usize,
+                    );
+                    return;
+                }
+
+                let mut flag = entry[1] as u16;
+
+                // Convert dungeonflag numbers to be like sceneflags
+                // Dungeonflags start offset by 1 due to an undefined value in the flag
+                // definitions.
+                if flag == 2 || flag == 3 || flag == 4 {
+                    flag -= 1;
+                } else if flag == 12 {
+                    // The rooms are defined before the boss key placed flag
+                    flag = 7;
+                } else if flag == 16 {
+                    flag = 8;
+                }
+
+                set_global_dungeonflag(entry[0].into(), flag);
+            },
+
+            // Tempflags and zoneflags are for the starting scene
+            STARTFLAG_TAG::TEMPFLAG => set_tempflag(value),
+
+            // entry = roomid, zoneflag
+            STARTFLAG_TAG::ZONEFLAG => {
+                if entry[0] > MAX_ROOMID {
+                    debug::debug_print_num(
+                        cstr!("Skipping zoneflag with invalid room: %d").as_ptr(),
+                        entry[0] as usize,
+                    );
+                    return;
+                }
+
+                set_zoneflag(entry[0], entry[1].into());
+            },
+
+            // entry = stage name, objectid
+            // The stage object flags only belong to one stage, which is the
+            // one the file starts in here. It isn't loaded yet so the save copy
+            // is written directly
+            STARTFLAG_TAG::STAGE_OBJECT_FLAG => {
+                let objectid = u16::from_le_bytes([entry[8], entry[9]]);
+
+                if !entrance::is_start_stage(&entry[..8]) {
+                    debug::debug_print_num(
+                        cstr!("Skipping stage object flag for another stage: %d").as_ptr(),
+                        objectid as usize,
+                    );
+                    return;
+                }
+
+                let pos = FlagPosition::new(0, objectid);
+                (*FILE_MGR).FA.stage_object_flags[pos.word] |= pos.mask;
+            },
+
+            // entry = counter, value
+            STARTFLAG_TAG::COUNTER => {
+                let counter = value;
+                let value = u16::from_le_bytes([entry[2], entry[3]]);
+
+                // Small key and heart counters are all below the itemflag count
+                if counter >= ITEMFLAG_COUNT {
+                    debug::debug_print_num(
+                        cstr!("Skipping startflag with invalid counter: %d").as_ptr(),
+                        counter as usize,
+                    );
+                    return;
+                }
+
+                // Total up Heart Pieces and Heart Containers for starting health
+                if counter == 0x5E {
+                    *starting_hearts += value;
+                } else if counter == 0x5D {
+                    *starting_hearts += value * 4;
+                }
+                // If the counter is less than 25, it's a dungeon scene
+                // for small key counts. Otherwise, it's a regular item flag counter.
+                // No dungeon is loaded yet so only the save copy is written
+                else if counter <= MAX_SCENEINDEX as u16 {
+                    (*FILE_MGR).FA.dungeonflags[counter as usize][DUNGEONFLAG_SMALL_KEY_COUNTER] =
+                        value;
+                } else {
+                    ((*(*ITEMFLAG_MGR).funcs).set_flag_or_counter_to_value)(
+                        ITEMFLAG_MGR,
+                        counter,
+                        value,
+                    );
+                }
+            },
+
+            // entry = storage, slot, itemid, extra
+            STARTFLAG_TAG::LOADOUT_ITEM => {
+                let slot = entry[1] as usize;
+                let itemid = u16::from_le_bytes([entry[2], entry[3]]);
+                let extra = u16::from_le_bytes([entry[4], entry[5]]);
+                // The extra data (e.g. shield durability) is in the upper half
+                let slot_value = (itemid as i32) | ((extra as i32) << 16);
+
+                if entry[0] == LOADOUT_STORAGE::POUCH as u8 && slot < POUCH_SLOT_COUNT {
+                    (*FILE_MGR).FA.pouch_items[slot] = slot_value;
+
+                    // The first slot is selected and the first shield is equipped
+                    if slot == 0 {
+                        (*FILE_MGR).FA.selected_pouch_slot = 0;
+                    }
+                    if is_shield(itemid) && !*shield_equipped {
+                        (*FILE_MGR).FA.shield_pouch_slot = slot as u8;
+                        *shield_equipped = true;
+                    }
+                } else if entry[0] == LOADOUT_STORAGE::ITEM_CHECK as u8
+                    && slot < ITEM_CHECK_SLOT_COUNT
+                {
+                    (*FILE_MGR).FA.item_check_items[slot] = slot_value;
+                } else {
+                    debug::debug_print_num(
+                        cstr!("Skipping loadout item with invalid slot: %d").as_ptr(),
+                        slot,
+                    );
+                }
+            },
+        }
+    }
+}
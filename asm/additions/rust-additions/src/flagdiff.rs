@@ -0,0 +1,228 @@
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(unused)]
+
+use crate::debug;
+use crate::event;
+use crate::flag;
+use crate::savefile;
+
+use core::arch::asm;
+use core::ffi::{c_char, c_void};
+use cstr::cstr;
+use static_assertions::assert_eq_size;
+
+// Only built with the `debug` feature. Logs every flag that changed during an
+// event or since the last stage load, which makes finding the flags a cutscene
+// sets (for startflags and ER fixups) a lot easier than diffing memory by hand
+
+// repr(C) prevents rust from reordering struct fields.
+// packed(1) prevents rust from aligning structs to the size of the largest
+// field.
+
+// Using u64 or 64bit pointers forces structs to be 8-byte aligned.
+// The vanilla code seems to be 4-byte aligned. To make extra sure, used
+// packed(1) to force the alignment to match what you define.
+
+// Always add an assert_eq_size!() macro after defining a struct to ensure it's
+// the size you expect it to be.
+
+// The live copy of the flags for the current scene and dungeon, and the save
+// copy for every other scene
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct FlagSnapshot {
+    pub storyflags:   [u16; 128],
+    pub itemflags:    [u16; 64],
+    pub sceneflags:   [[u16; 8]; 26],
+    pub dungeonflags: [[u16; 8]; 26],
+    pub tempflags:    [u16; 4],
+    pub zoneflags:    [[u16; 4]; 63],
+    pub sceneindex:   u16,
+    pub in_event:     u8,
+    pub taken:        u8,
+}
+assert_eq_size!([u8; 0x6C4], FlagSnapshot);
+
+// IMPORTANT: when using vanilla code, the start point must be declared in
+// symbols.yaml and then added to this extern block.
+extern "C" {
+    static FILE_MGR: *mut savefile::FileMgr;
+    static SCENEFLAG_MGR: *mut flag::SceneflagMgr;
+    static DUNGEONFLAG_MGR: *mut flag::DungeonflagMgr;
+
+    static STATIC_STORYFLAGS: [u16; 128];
+    static STATIC_SCENEFLAGS: [u16; 8];
+    static STATIC_TEMPFLAGS: [u16; 4];
+    static STATIC_ZONEFLAGS: [[u16; 4]; 63];
+    static STATIC_ITEMFLAGS: [u16; 64];
+    static STATIC_DUNGEONFLAGS: [u16; 8];
+
+    // Custom symbols
+    static mut FLAG_SNAPSHOT: FlagSnapshot;
+
+    // Functions
+    fn debugPrint_128(string: *const c_char, fstr: *const c_char, ...);
+}
+
+// IMPORTANT: when adding functions here that need to get called from the game,
+// add `#[no_mangle]` and add a .global *symbolname* to
+// additions/rust-additions.asm
+
+fn take_flag_snapshot(sceneindex: u16, in_event: bool) -> FlagSnapshot {
+    unsafe {
+        let mut snapshot = FlagSnapshot {
+            storyflags: STATIC_STORYFLAGS,
+            itemflags: STATIC_ITEMFLAGS,
+            sceneflags: (*FILE_MGR).FA.sceneflags,
+            dungeonflags: (*FILE_MGR).FA.dungeonflags,
+            tempflags: STATIC_TEMPFLAGS,
+            zoneflags: STATIC_ZONEFLAGS,
+            sceneindex,
+            in_event: in_event as u8,
+            taken: 1,
+        };
+
+        if sceneindex <= flag::MAX_SCENEINDEX.into() {
+            snapshot.sceneflags[sceneindex as usize] = STATIC_SCENEFLAGS;
+        }
+
+        if !DUNGEONFLAG_MGR.is_null() {
+            let dungeon_sceneindex = (*DUNGEONFLAG_MGR).sceneindex;
+            if dungeon_sceneindex <= flag::MAX_SCENEINDEX {
+                snapshot.dungeonflags[dungeon_sceneindex as usize] = STATIC_DUNGEONFLAGS;
+            }
+        }
+
+        return snapshot;
+    }
+}
+
+// `index` is the scene or room the flags belong to, if the flag space has them
+fn log_changed_flags(
+    set_message: *const c_char,
+    unset_message: *const c_char,
+    index: Option<usize>,
+    old_flags: &[u16],
+    new_flags: &[u16],
+) {
+    for (word, (old, new)) in old_flags.iter().zip(new_flags.iter()).enumerate() {
+        let changed = old ^ new;
+        for bit in 0..16 {
+            if changed & (1 << bit) == 0 {
+                continue;
+            }
+
+            let flag = word * 16 + bit;
+            let message = if new & (1 << bit) != 0 {
+                set_message
+            } else {
+                unset_message
+            };
+
+            match index {
+                Some(index) => debug::debug_print_nums(message, index, flag),
+                None => debug::debug_print_num(message, flag),
+            }
+        }
+    }
+}
+
+// Tempflags and zoneflags are cleared by stage loads, so they're only compared
+// when the scene hasn't changed
+fn log_flag_snapshot_diff(old: &FlagSnapshot, new: &FlagSnapshot, same_scene: bool) {
+    log_changed_flags(
+        cstr!("Storyflag set: %d").as_ptr(),
+        cstr!("Storyflag unset: %d").as_ptr(),
+        None,
+        &{ old.storyflags },
+        &{ new.storyflags },
+    );
+    log_changed_flags(
+        cstr!("Itemflag set: %d").as_ptr(),
+        cstr!("Itemflag unset: %d").as_ptr(),
+        None,
+        &{ old.itemflags },
+        &{ new.itemflags },
+    );
+
+    for sceneindex in 0..=flag::MAX_SCENEINDEX as usize {
+        log_changed_flags(
+            cstr!("Sceneflag set (scene %d, flag %d)").as_ptr(),
+            cstr!("Sceneflag unset (scene %d, flag %d)").as_ptr(),
+            Some(sceneindex),
+            &{ old.sceneflags }[sceneindex],
+            &{ new.sceneflags }[sceneindex],
+        );
+        log_changed_flags(
+            cstr!("Dungeonflag set (scene %d, flag %d)").as_ptr(),
+            cstr!("Dungeonflag unset (scene %d, flag %d)").as_ptr(),
+            Some(sceneindex),
+            &{ old.dungeonflags }[sceneindex],
+            &{ new.dungeonflags }[sceneindex],
+        );
+    }
+
+    if !same_scene {
+        return;
+    }
+
+    log_changed_flags(
+        cstr!("Tempflag set: %d").as_ptr(),
+        cstr!("Tempflag unset: %d").as_ptr(),
+        None,
+        &{ old.tempflags },
+        &{ new.tempflags },
+    );
+
+    for roomid in 0..=flag::MAX_ROOMID as usize {
+        log_changed_flags(
+            cstr!("Zoneflag set (room %d, flag %d)").as_ptr(),
+            cstr!("Zoneflag unset (room %d, flag %d)").as_ptr(),
+            Some(roomid),
+            &{ old.zoneflags }[roomid],
+            &{ new.zoneflags }[roomid],
+        );
+    }
+}
+
+// Called every frame from the main loop. The first snapshot is only taken,
+// after that the flags are compared whenever an event ends or a new scene
+// has loaded
+pub fn update_flag_snapshot() {
+    unsafe {
+        if FILE_MGR.is_null() || SCENEFLAG_MGR.is_null() {
+            return;
+        }
+
+        let snapshot = &mut *core::ptr::addr_of_mut!(FLAG_SNAPSHOT);
+        let sceneindex = (*SCENEFLAG_MGR).sceneindex;
+        let in_event = event::is_in_event();
+
+        if snapshot.taken == 0 {
+            *snapshot = take_flag_snapshot(sceneindex, in_event);
+            return;
+        }
+
+        let event_ended = snapshot.in_event != 0 && !in_event;
+        let stage_loaded = snapshot.sceneindex != sceneindex;
+        snapshot.in_event = in_event as u8;
+
+        if !event_ended && !stage_loaded {
+            return;
+        }
+
+        if stage_loaded {
+            debug::debug_print_num(
+                cstr!("Flag changes up to loading scene %d").as_ptr(),
+                sceneindex as usize,
+            );
+        } else {
+            debug::debug_print(cstr!("Flag changes during the event").as_ptr());
+        }
+
+        let new_snapshot = take_flag_snapshot(sceneindex, in_event);
+        log_flag_snapshot_diff(snapshot, &new_snapshot, !stage_loaded);
+        *snapshot = new_snapshot;
+    }
+}
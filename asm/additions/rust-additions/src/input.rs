@@ -3,6 +3,7 @@
 #![allow(unused)]
 
 use crate::debug;
+use crate::settings;
 
 use core::arch::asm;
 use core::ffi::{c_char, c_void};
@@ -64,11 +65,41 @@ pub enum BUTTON_INPUTS {
     LEFT_STICK_RIGHT   = 0x8000000,
 }
 
+const ALL_BUTTON_INPUTS: [BUTTON_INPUTS; 24] = [
+    BUTTON_INPUTS::DPAD_LEFT_BUTTON,
+    BUTTON_INPUTS::DPAD_RIGHT_BUTTON,
+    BUTTON_INPUTS::DPAD_DOWN_BUTTON,
+    BUTTON_INPUTS::DPAD_UP_BUTTON,
+    BUTTON_INPUTS::PLUS_BUTTON,
+    BUTTON_INPUTS::Y_BUTTON,
+    BUTTON_INPUTS::X_BUTTON,
+    BUTTON_INPUTS::B_BUTTON,
+    BUTTON_INPUTS::A_BUTTON,
+    BUTTON_INPUTS::MINUS_BUTTON,
+    BUTTON_INPUTS::ZL_BUTTON,
+    BUTTON_INPUTS::L_BUTTON,
+    BUTTON_INPUTS::LEFT_STICK_BUTTON,
+    BUTTON_INPUTS::RIGHT_STICK_BUTTON,
+    BUTTON_INPUTS::R_BUTTON,
+    BUTTON_INPUTS::ZR_BUTTON,
+    BUTTON_INPUTS::RIGHT_STICK_UP,
+    BUTTON_INPUTS::RIGHT_STICK_DOWN,
+    BUTTON_INPUTS::RIGHT_STICK_LEFT,
+    BUTTON_INPUTS::RIGHT_STICK_RIGHT,
+    BUTTON_INPUTS::LEFT_STICK_UP,
+    BUTTON_INPUTS::LEFT_STICK_DOWN,
+    BUTTON_INPUTS::LEFT_STICK_LEFT,
+    BUTTON_INPUTS::LEFT_STICK_RIGHT,
+];
+
 // IMPORTANT: when using vanilla code, the start point must be declared in
 // symbols.yaml and then added to this extern block.
 extern "C" {
     static INPUT_MGR: *mut InputMgr;
 
+    // Custom symbols
+    static mut BUTTON_COMBO_HELD_FRAMES: [u16; settings::BUTTON_COMBOS_COUNT];
+
     // Functions
     fn debugPrint_128(string: *const c_char, fstr: *const c_char, ...);
 }
@@ -90,3 +121,52 @@ pub fn check_button_pressed(button: BUTTON_INPUTS) -> bool {
         return ((*(*INPUT_MGR).vtable).check_button_pressed)(INPUT_MGR, button);
     }
 }
+
+// True if every button in the mask (BUTTON_INPUTS ORed together) is held
+pub fn check_buttons_held(buttons: u32) -> bool {
+    if buttons == 0 {
+        return false;
+    }
+
+    for &button in ALL_BUTTON_INPUTS.iter() {
+        if buttons & (button as u32) != 0 && !check_button_held(button) {
+            return false;
+        }
+    }
+
+    return true;
+}
+
+// Should be called once per frame from the main loop
+#[no_mangle]
+pub fn update_button_combos() {
+    unsafe {
+        let held_frames = &mut *core::ptr::addr_of_mut!(BUTTON_COMBO_HELD_FRAMES);
+
+        let combos = match settings::get_randomizer_settings() {
+            Some(settings) => settings.button_combos,
+            None => {
+                held_frames.fill(0);
+                return;
+            },
+        };
+
+        for (combo, held_frames) in combos.iter().zip(held_frames.iter_mut()) {
+            if combo.enabled != 0 && check_buttons_held(combo.buttons) {
+                *held_frames = held_frames.saturating_add(1);
+            } else {
+                *held_frames = 0;
+            }
+        }
+    }
+}
+
+pub fn check_button_combo_held(combo_index: usize) -> bool {
+    unsafe {
+        let combo = match settings::get_randomizer_settings() {
+            Some(settings) => settings.button_combos[combo_index],
+            None => return false,
+        };
+        return combo.enabled != 0 && BUTTON_COMBO_HELD_FRAMES[combo_index] > combo.hold_frames;
+    }
+}
@@ -3,14 +3,17 @@
 #![allow(unused)]
 
 use crate::actor;
+use crate::customitem;
 use crate::debug;
 use crate::event;
 use crate::fix;
 use crate::flag;
 use crate::math;
+use crate::observer;
 use crate::player;
 use crate::savefile;
 use crate::settings;
+use crate::tracker;
 
 use core::arch::asm;
 use core::ffi::{c_char, c_void};
@@ -99,6 +102,82 @@ pub struct dAcTbox {
 }
 assert_eq_size!([u8; 0x19A8], dAcTbox);
 
+// How an item is shown in the world, one entry per item id. The patcher writes
+// the table from data/item_display_info.yaml
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct ItemDisplayInfo {
+    pub y_offset:           f32,
+    pub wall_offset:        f32,
+    pub model_scale:        f32,
+    pub freestanding_scale: f32,
+    pub wall_rotation_x:    u16,
+    pub wall_rotation_y:    u16,
+    pub wall_rotation_z:    u16,
+    pub flags:              u8,
+    pub _0:                 u8,
+}
+assert_eq_size!([u8; 24], ItemDisplayInfo);
+
+pub const ITEM_DISPLAY_INFO_COUNT: usize = 256;
+pub const ITEM_DISPLAY_FLAG_DEFAULT_SCALING: u8 = 1 << 0;
+
+pub const DEFAULT_ITEM_DISPLAY_INFO: ItemDisplayInfo = ItemDisplayInfo {
+    y_offset:           0.0,
+    wall_offset:        0.0,
+    model_scale:        1.0,
+    freestanding_scale: 1.0,
+    wall_rotation_x:    0,
+    wall_rotation_y:    0,
+    wall_rotation_z:    0,
+    flags:              0,
+    _0:                 0,
+};
+
+// A progressive item is a chain of stages that are given in order. The
+// patcher writes the chains from data/progressive_items.yaml, the table ends
+// at the first chain with a base_itemid of 0
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct ProgressiveItemStage {
+    pub flag:       flag::FlagRef,
+    pub itemid:     u16,
+    pub text:       u16,
+    pub arc_name:   [u8; 16],
+    pub model_name: [u8; 16],
+}
+assert_eq_size!([u8; 40], ProgressiveItemStage);
+
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct ProgressiveItemChain {
+    pub base_itemid: u16,
+    pub stage_count: u8,
+    pub _0:          u8,
+    pub stages:      [ProgressiveItemStage; MAX_PROGRESSIVE_ITEM_STAGES],
+}
+assert_eq_size!([u8; 244], ProgressiveItemChain);
+
+pub const MAX_PROGRESSIVE_ITEM_CHAINS: usize = 16;
+pub const MAX_PROGRESSIVE_ITEM_STAGES: usize = 6;
+
+// An item that's tied to a dungeon (boss keys, small keys, maps, rewards and
+// so on). The patcher writes the table from data/dungeon_items.yaml, it ends
+// at the first entry with an itemid of 0. Entries with an amount add it to the
+// counter `flag` points at (for dungeonflags that's the counter slot), the
+// others set the flag
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct DungeonItem {
+    pub itemid: u16,
+    pub amount: u8,
+    pub _0:     u8,
+    pub flag:   flag::FlagRef,
+}
+assert_eq_size!([u8; 8], DungeonItem);
+
+pub const MAX_DUNGEON_ITEMS: usize = 64;
+
 // IMPORTANT: when using vanilla code, the start point must be declared in
 // symbols.yaml and then added to this extern block.
 extern "C" {
@@ -120,8 +199,13 @@ extern "C" {
 
     static mut SQUIRRELS_CAUGHT_THIS_PLAY_SESSION: bool;
 
-    static RANDOMIZER_SETTINGS: settings::RandomizerSettings;
     static mut dAcOWarp__StateGateOpen: c_void;
+
+    // Custom symbols
+    static ITEM_DISPLAY_INFO: [ItemDisplayInfo; ITEM_DISPLAY_INFO_COUNT];
+    static PROGRESSIVE_ITEM_CHAINS: [ProgressiveItemChain; MAX_PROGRESSIVE_ITEM_CHAINS];
+    static DUNGEON_ITEMS: [DungeonItem; MAX_DUNGEON_ITEMS];
+
     // Functions
     fn debugPrint_128(string: *const c_char, fstr: *const c_char, ...);
     fn sinf(x: f32) -> f32;
@@ -266,6 +350,10 @@ pub fn handle_crest_hit_give_item(crest_actor: *mut actor::dAcOSwSwordBeam) {
                 ((*crest_actor).base.basebase.members.param1 >> 0x18) as u8;
             give_item(goddess_sword_reward);
             flag::set_local_sceneflag(50);
+            tracker::log_location_check(
+                tracker::local_location_id(tracker::LOCATION_KIND::SCENEFLAG, 50),
+                goddess_sword_reward as u16,
+            );
         }
         if (EQUIPPED_SWORD < 2) {
             return;
@@ -276,6 +364,10 @@ pub fn handle_crest_hit_give_item(crest_actor: *mut actor::dAcOSwSwordBeam) {
             let longsword_reward: u8 = ((*crest_actor).base.basebase.members.param1 >> 0x10) as u8;
             give_item(longsword_reward);
             flag::set_local_sceneflag(51);
+            tracker::log_location_check(
+                tracker::local_location_id(tracker::LOCATION_KIND::SCENEFLAG, 51),
+                longsword_reward as u16,
+            );
         }
         if (EQUIPPED_SWORD < 3) {
             return;
@@ -286,132 +378,60 @@ pub fn handle_crest_hit_give_item(crest_actor: *mut actor::dAcOSwSwordBeam) {
             let whitesword_reward: u8 = ((*crest_actor).base.members.base.param2 >> 0x18) as u8;
             give_item(whitesword_reward);
             flag::set_local_sceneflag(52);
+            tracker::log_location_check(
+                tracker::local_location_id(tracker::LOCATION_KIND::SCENEFLAG, 52),
+                whitesword_reward as u16,
+            );
         }
     }
 }
 
 #[no_mangle]
 pub fn handle_custom_item_get(item_actor: *mut dAcItem) -> u16 {
-    const BK_TO_FLAGINDEX: [usize; 7] = [
-        12,  // AC BK - item id 25
-        15,  // FS BK - item id 26
-        18,  // SSH BK - item id 27
-        255, // unused, shouldn't happen
-        11,  // SV BK - item id 29
-        14,  // ET - item id 30
-        17,  // LMF - item id 31
-    ];
-
-    const SK_TO_FLAGINDEX: [usize; 7] = [
-        11, // SV SK - item id 200
-        17, // LMF SK - item id 201
-        12, // AC SK - item id 202
-        15, // FS SK - item id 203
-        18, // SSH SK - item id 204
-        20, // SK SK - item id 205
-        9,  // Caves SK - item id 206
-    ];
-
-    const MAP_TO_FLAGINDEX: [usize; 7] = [
-        11, // SV MAP - item id 207
-        14, // ET MAP - item id 208
-        17, // LMF MAP - item id 209
-        12, // AC MAP - item id 210
-        15, // FS MAP - item id 211
-        18, // SSH MAP - item id 212
-        20, // SK MAP - item id 213
-    ];
-
     unsafe {
         let itemid = (*item_actor).itemid;
 
-        let mut dungeon_item_mask = 0;
-
-        if (itemid >= 25 && itemid <= 27) || (itemid >= 29 && itemid <= 31) {
-            dungeon_item_mask = 0x80; // boss keys
-        }
-
-        if dungeon_item_mask == 0 {
-            if itemid >= 200 && itemid <= 206 {
-                dungeon_item_mask = 0x0F; // small keys
-            }
+        if let Some(dungeon_item) = get_dungeon_item(itemid) {
+            give_dungeon_item(dungeon_item);
         }
 
-        if dungeon_item_mask == 0 {
-            if itemid >= 207 && itemid <= 213 {
-                dungeon_item_mask = 0x02; // maps
-            }
-        }
-
-        if dungeon_item_mask != 0 {
-            let current_scene_index = (*DUNGEONFLAG_MGR).sceneindex as usize;
-            let mut dungeon_item_scene_index = 0xFF;
-
-            if dungeon_item_mask == 0x80 {
-                dungeon_item_scene_index = BK_TO_FLAGINDEX[(itemid - 25) as usize];
-            }
+        customitem::handle_custom_item_collected(itemid);
 
-            if dungeon_item_mask == 0x0F {
-                dungeon_item_scene_index = SK_TO_FLAGINDEX[(itemid - 200) as usize];
-            }
+        // Set the location flag if this item has one
+        let (location_flag, original_itemid) = unpack_custom_item_params(item_actor);
 
-            if dungeon_item_mask == 0x02 {
-                dungeon_item_scene_index = MAP_TO_FLAGINDEX[(itemid - 207) as usize];
-            }
-
-            // Set the local flag if the item is in its vanilla scene.
-            if current_scene_index == dungeon_item_scene_index {
-                if dungeon_item_mask != 0x0F {
-                    STATIC_DUNGEONFLAGS[0] |= dungeon_item_mask;
-                } else {
-                    STATIC_DUNGEONFLAGS[1] += 1;
-                }
-            }
-            // Otherwise, set the global flag.
-            if dungeon_item_mask != 0x0F {
-                (*FILE_MGR).FA.dungeonflags[dungeon_item_scene_index][0] |= dungeon_item_mask;
-            } else {
-                (*FILE_MGR).FA.dungeonflags[dungeon_item_scene_index][1] += 1;
-            }
+        if location_flag != flag::LOCATION_FLAG_NONE {
+            flag::set_location_flag(location_flag);
         }
 
-        // Get necessary params for setting a custom flag if this item has one
-        let (flag, sceneindex, flag_space_trigger, original_itemid) =
-            unpack_custom_item_params(item_actor);
-
-        if flag != 0x7F {
-            // Use different flag spaces depending on the value of the
-            // flag_space_trigger
-            match flag_space_trigger {
-                0 => flag::set_global_sceneflag(sceneindex as u16, flag as u16),
-                1 => flag::set_global_dungeonflag(sceneindex as u16, flag as u16),
-                _ => {},
-            }
+        // Log the location this item came from. Items spawned by squirrels and
+        // tgreacts carry their flag with them so they're logged here too
+        let sceneflag = ((*item_actor).base.basebase.members.param1 >> 10) & 0xFF;
+        if location_flag != flag::LOCATION_FLAG_NONE {
+            tracker::log_location_check(
+                tracker::location_id(tracker::LOCATION_KIND::LOCATION_FLAG, 0, location_flag),
+                itemid,
+            );
+        } else if sceneflag != 0xFF && tracker::is_randomized_sceneflag(sceneflag as u8) {
+            tracker::log_location_check(
+                tracker::local_location_id(tracker::LOCATION_KIND::SCENEFLAG, sceneflag as u16),
+                itemid,
+            );
         }
 
         return (*item_actor).final_determined_itemid;
     }
 }
 
-// Unpacks our custom item params into separate variables
+// Unpacks our custom item params into the location flag and the itemid the
+// item reverts to once the location flag is set
 #[no_mangle]
-pub fn unpack_custom_item_params(item_actor: *mut dAcItem) -> (u32, u32, u32, u32) {
+pub fn unpack_custom_item_params(item_actor: *mut dAcItem) -> (u16, u32) {
     unsafe {
         let param2: u32 = (*item_actor).base.members.base.param2;
-        let flag: u32 = (param2 & (0x00007F00)) >> 8;
-        let mut sceneindex: u32 = (param2 & (0x00018000)) >> 15;
-        let flag_space_trigger: u32 = (param2 & (0x00020000)) >> 17;
+        let location_flag = flag::get_actor_location_flag(param2);
         let mut original_itemid: u32 = (param2 & (0x00FC0000)) >> 18;
 
-        // Transform the scene index into one of the unused ones
-        match sceneindex {
-            0 => sceneindex = 6,
-            1 => sceneindex = 13,
-            2 => sceneindex = 16,
-            3 => sceneindex = 19,
-            _ => {},
-        }
-
         // Transform the original_itemid into its proper itemid
         match original_itemid {
             1 => original_itemid = 42, // Stamina Fruit
@@ -422,16 +442,15 @@ pub fn unpack_custom_item_params(item_actor: *mut dAcItem) -> (u32, u32, u32, u3
             _ => {},
         }
 
-        return (flag, sceneindex, flag_space_trigger, original_itemid);
+        return (location_flag, original_itemid);
     }
 }
 
 #[no_mangle]
 pub fn check_and_modify_item_actor(item_actor: *mut dAcItem) {
     unsafe {
-        // Get necessary params for checking if this item has a custom flag
-        let (flag, sceneindex, flag_space_trigger, original_itemid) =
-            unpack_custom_item_params(item_actor);
+        // Get necessary params for checking if this item has a location flag
+        let (location_flag, original_itemid) = unpack_custom_item_params(item_actor);
 
         // Don't do anything for conveyor spawned stamina fruit in LMF
         let current_item = (*item_actor).base.basebase.members.param1 & 0x1FF;
@@ -440,16 +459,25 @@ pub fn check_and_modify_item_actor(item_actor: *mut dAcItem) {
             return;
         }
 
-        // Don't give a textbox for the specified items, otherwise, force a textbox
-        match current_item {
-            // Green | Blue | Red Rupee | Heart, Arrows | Bombs, Stamina, Tears, Light Fruit | Seeds
-            // | Uncommon | Rare Treasure | Bugs | Treasures
-            2 | 3 | 4 | 6..=8 | 40..=47 | 57 | 60 | 63 | 64 | 141..=152 | 161..=176 => {
-                (*item_actor).base.basebase.members.param1 |= 0x200;
-            },
-            _ => {
+        // Custom items decide for themselves whether they get a textbox
+        if let Some(custom_item) = customitem::get_custom_item(current_item as u16) {
+            if custom_item.textbox {
                 (*item_actor).base.basebase.members.param1 &= !0x200u32;
-            },
+            } else {
+                (*item_actor).base.basebase.members.param1 |= 0x200;
+            }
+        } else {
+            // Don't give a textbox for the specified items, otherwise, force a textbox
+            match current_item {
+                // Green | Blue | Red Rupee | Heart, Arrows | Bombs, Stamina, Tears, Light Fruit |
+                // Seeds | Uncommon | Rare Treasure | Bugs | Treasures
+                2 | 3 | 4 | 6..=8 | 40..=47 | 57 | 60 | 63 | 64 | 141..=152 | 161..=176 => {
+                    (*item_actor).base.basebase.members.param1 |= 0x200;
+                },
+                _ => {
+                    (*item_actor).base.basebase.members.param1 &= !0x200u32;
+                },
+            }
         }
 
         // Despawn the item if it's one of the stamina fruit on LMF that
@@ -464,24 +492,17 @@ pub fn check_and_modify_item_actor(item_actor: *mut dAcItem) {
             (*item_actor).base.basebase.members.param1 &= !0x1FF;
         }
 
-        // Check if the flag is on
-        let mut flag_is_on = 0;
-        match flag_space_trigger {
-            0 => flag_is_on = flag::check_global_sceneflag(sceneindex as u16, flag as u16),
-            1 => flag_is_on = flag::check_global_dungeonflag(sceneindex as u16, flag as u16),
-            _ => {},
-        }
-
-        // If we have a custom flag and it's been set, revert this item back to what
-        // it originally was
-        if flag != 0x7F && flag_is_on != 0 {
+        // If we have a location flag and it's been set, revert this item back to
+        // what it originally was
+        let has_location_flag = location_flag != flag::LOCATION_FLAG_NONE;
+        if has_location_flag && flag::check_location_flag(location_flag) != 0 {
             (*item_actor).base.basebase.members.param1 &= !0x1FF;
             (*item_actor).base.basebase.members.param1 |= original_itemid;
             // Set bit 9 for no textbox
             (*item_actor).base.basebase.members.param1 |= 0x200;
-        // Otherwise, if we have a custom flag, potentially fix
+        // Otherwise, if we have a location flag, potentially fix
         // the horizontal offset if necessary
-        } else if (flag != 0x7F) {
+        } else if has_location_flag {
             fix_freestanding_item_horizontal_offset(item_actor);
         }
 
@@ -521,6 +542,8 @@ pub fn give_squirrel_item(musasabi_tag: *mut actor::dTgMusasabi) {
             let itemid: u8 = ((*musasabi_tag).base.members.param2 & 0xFF) as u8;
             let sceneflag: u8 = ((*musasabi_tag).base.members.param2 >> 8 & 0xFF) as u8;
 
+            // The item keeps the sceneflag so handle_custom_item_get logs the
+            // location check once it's collected
             if sceneflag != u8::MAX && flag::check_local_sceneflag(sceneflag as u32) == 0 {
                 give_item_with_sceneflag(itemid, sceneflag);
             } else {
@@ -559,27 +582,9 @@ pub fn tgreact_spawn_custom_item(
         let tgreact_param1: u32 = (*tgreact).basebase.members.param1;
         let param2 = (*tgreact).members.base.param2;
 
-        if (param2 >> 8) & 0x3FF != 0x3FF {
-            let flag: u32 = (param2 & (0x00007F00)) >> 8;
-
-            let mut sceneindex: u32 = (param2 & (0x00018000)) >> 15;
-            match sceneindex {
-                0 => sceneindex = 6,
-                1 => sceneindex = 13,
-                2 => sceneindex = 16,
-                3 => sceneindex = 19,
-                _ => {},
-            }
-
-            let flag_space_trigger: u32 = (param2 & (0x00020000)) >> 17;
-
-            // Check if the flag is on
-            let mut flag_is_on = 0;
-            match flag_space_trigger {
-                0 => flag_is_on = flag::check_global_sceneflag(sceneindex as u16, flag as u16),
-                1 => flag_is_on = flag::check_global_dungeonflag(sceneindex as u16, flag as u16),
-                _ => {},
-            }
+        let location_flag = flag::get_actor_location_flag(param2);
+        if location_flag != flag::LOCATION_FLAG_NONE {
+            let flag_is_on = flag::check_location_flag(location_flag);
 
             let new_itemid = dAcItem__determineFinalItemid(((tgreact_param1 >> 8) & 0xFF) as u64);
 
@@ -607,6 +612,8 @@ pub fn tgreact_spawn_custom_item(
 
                 let trapid = (param2 >> 19) & 0xF;
 
+                // The location flag is passed on to the item so the location check
+                // is logged by handle_custom_item_get if it actually gets collected
                 let item_actor: *mut dAcItem = actor::spawn_actor(
                     actor::ACTORID::ITEM,
                     roomid,
@@ -726,108 +733,36 @@ pub fn rotate_freestanding_items(item_actor: *mut dAcItem) {
     }
 }
 
+// Custom items keep their display info in their registry entry
+pub fn get_item_display_info(itemid: u16) -> ItemDisplayInfo {
+    if let Some(custom_item) = customitem::get_custom_item(itemid) {
+        return custom_item.display;
+    }
+
+    unsafe {
+        if itemid as usize >= ITEM_DISPLAY_INFO_COUNT {
+            return DEFAULT_ITEM_DISPLAY_INFO;
+        }
+        return ITEM_DISPLAY_INFO[itemid as usize];
+    }
+}
+
 #[no_mangle]
 pub fn fix_freestanding_item_y_offset(item_actor: *mut dAcItem) {
     unsafe {
         let actor_param1 = (*item_actor).base.basebase.members.param1;
 
         if (*item_actor).itemid != 42 {
-            let mut use_default_scaling = false;
-            let mut y_offset = 0.0f32;
             let item_rot = (*item_actor).base.members.base.rot;
-
-            // Item id
-            match dAcItem__determineFinalItemid((actor_param1 & 0x1FF).into()) {
-                // Sword + Sailcloth | Harp | Digging Mitts | Scattershot | Beedle's Insect Cage | Ancient Flower | Sot | Songs
-                9..=15 | 16 | 56 | 105 | 159 | 166 | 180 | 186..=193 => y_offset = 20.0,
-                // Bow | Iron Bow | Sacred Bow | Sea Chart | Wooden Shield | Hylian Shield
-                19 | 90 | 91 | 98 | 116 | 125 => y_offset = 23.0,
-                // Clawshots | Spiral Charge | Mogma Mitts | Life Tree Seedling
-                20 | 21 | 99 | 197 => y_offset = 25.0,
-                // AC BK | FS BK
-                25 | 26 => y_offset = 30.0,
-                // SSH BK, ET Key, SV BK, ET BK | Amber Tablet
-                27..=30 | 99 | 179 => y_offset = 24.0,
-                // LMF BK
-                31 => y_offset = 27.0,
-                // Crystal Pack | 5 Bombs | Single Crystal | Beetle | Pouch | Pouch Expansion | Small Bomb Bag | Big Bug Net | Eldin Ore
-                35 | 40 | 48 | 53 | 112 | 113 | 134 | 140 | 165 => y_offset = 18.0,
-                // Bellows | Bug Net | Bomb Bag
-                49 | 71 | 92 => y_offset = 26.0,
-                36          // Glittering Spores
-                | 52        // Slingshot
-                | 54        // Bottle of Water
-                | 55        // Mushroom Spores
-                | 65        // Guardian Potion
-                | 66        // Guardian Potion+
-                | 68        // Water Dragon's Scale
-                | 70        // Bug Medal
-                | 74        // Sacred Water
-                | 78        // Heart Potion
-                | 79        // Heart Potion+
-                | 81        // Heart Potion++
-                | 84        // Stamina Potion
-                | 85        // Stamina Potion+
-                | 86        // Air Potion
-                | 87        // Air Potion+
-                | 88        // Fairy in a Bottle
-                | 100..=104 // Medals
-                | 108..=111 // Wallets
-                | 114       // Life Medal
-                | 126       // Revitalizing Potion
-                | 127       // Revitalizing Potion+
-                | 153       // Empty Bottle
-                | 161..=164 // Treasures
-                | 167..=170 // Treasures
-                | 172..=174 // Treasures
-                | 178       // Ruby Tablet
-                | 194       // Revitalizing Potion++
-                | 195       // Hot Pumpkin Soup
-                | 196       // Cold Pumpkin Soup
-                | 198       // Life Tree Fruit
-                | 199 => y_offset = 16.0,
-                // 10 Deku Seeds
-                60 => {
-                    y_offset = 15.0;
-                    use_default_scaling = true;
-                },
-                // Beetle Upgrades
-                75..=77 => y_offset = 10.0,
-                // Heart Container
-                93 => use_default_scaling = true,
-                // Triforces
-                95..=97 => {
-                    y_offset = 24.0;
-                    use_default_scaling = true;
-                },
-                // Seed Satchel | Golden Skull
-                128 | 175 => y_offset = 14.0,
-                // Map | Quiver | Whip | Emerald Tablet | Maps
-                50 | 131 | 137 | 177 | 207..=213 => y_offset = 19.0,
-                // Earrings
-                138 => y_offset = 6.0,
-                // Letter | Monster Horn
-                158 | 171 => y_offset = 12.0,
-                // Rattle
-                160 => {
-                    y_offset = 5.0;
-                    use_default_scaling = true;
-                },
-                // Goddess Plume
-                176 => y_offset = 17.0,
-                // 10 Bombs | 5 Deku Seeds
-                41 | 57 => use_default_scaling = true,
-                // Heart | 10 Arrows
-                6 | 8 => use_default_scaling = true,
-                _ => y_offset = 0.0,
-            }
+            let itemid = dAcItem__determineFinalItemid((actor_param1 & 0x1FF).into());
+            let display_info = get_item_display_info(itemid as u16);
 
             // Only apply the offset if the item isn't tilted
             if item_rot.x < 0x2000 || item_rot.x > 0xE000 {
-                (*item_actor).freestanding_y_offset = y_offset;
+                (*item_actor).freestanding_y_offset = display_info.y_offset;
             }
 
-            if use_default_scaling {
+            if display_info.flags & ITEM_DISPLAY_FLAG_DEFAULT_SCALING != 0 {
                 (*item_actor).base.members.base.rot.y |= 1;
             } else {
                 (*item_actor).base.members.base.rot.y &= 0xFFFE;
@@ -844,209 +779,8 @@ pub fn fix_freestanding_item_horizontal_offset(item_actor: *mut dAcItem) {
         let item_rot = (*item_actor).base.members.base.rot;
         if item_rot.x > 0x2000 && item_rot.x < 0xE000 {
             let actor_param1 = (*item_actor).base.basebase.members.param1;
-            let mut h_offset = 0.0f32;
-            let mut angle_change_x = 0u16;
-            let mut angle_change_y = 0u16;
-            let mut angle_change_z = 0u16;
-
-            // Item id
-            match actor_param1 & 0x1FF {
-                // Rupees
-                2 | 3 | 4 | 32 | 33 | 34 => h_offset = 20.0,
-                // Progressive Sword
-                9..=14 => {
-                    h_offset = 7.0;
-                    angle_change_x = 0xD900;
-                    angle_change_y = 0xF400;
-                    angle_change_z = 0xF600;
-                },
-                // Goddess's Harp | All Songs
-                16 | 186..=193 => {
-                    h_offset = 17.0;
-                    angle_change_x = 0x0800;
-                    angle_change_y = 0x2500;
-                    angle_change_z = 0x0800;
-                },
-                // Progressive Bow
-                19 | 90 | 91 => h_offset = 17.0,
-                // Clawshots
-                20 => {
-                    h_offset = 25.0;
-                    angle_change_x = 0x0500;
-                    angle_change_y = 0x2400;
-                },
-                // Spiral Charge
-                21 => {
-                    h_offset = 27.0;
-                    angle_change_y = 0x3000;
-                    angle_change_z = 0x0300;
-                },
-                // AC BK
-                25 => {
-                    h_offset = 50.0;
-                    angle_change_x = 0xEF00;
-                },
-                // FS BK | SV BK
-                26 | 29 => h_offset = 40.0,
-                // SSH BK
-                27 => h_offset = 47.0,
-                // Key Piece
-                28 => {
-                    h_offset = 10.0;
-                    angle_change_x = 0x0800;
-                    angle_change_y = 0x2000;
-                    angle_change_z = 0x0800;
-                },
-                // ET BK
-                30 => h_offset = 60.0,
-                // LMF BK | Small Seed Satchel | Whip
-                31 | 128 | 137 => h_offset = 25.0,
-                // Gratitude Crystal Pack | Single Crystal
-                35 | 48 => h_offset = 28.0,
-                // 5 Bombs | 10 Bombs
-                40 | 41 => {
-                    h_offset = 20.0;
-                    angle_change_y = 0x1600;
-                },
-                // Gust Bellows
-                49 => {
-                    h_offset = 35.0;
-                    angle_change_x = 0x1100;
-                    angle_change_z = 0x2000;
-                },
-                // Progressive Slingshot
-                52 | 105 => {
-                    h_offset = 30.0;
-                    angle_change_x = 0x1000;
-                    angle_change_z = 0x1000;
-                },
-                // Progressive Beetle
-                53 | 75..=77 => {
-                    h_offset = 40.0;
-                    angle_change_x = 0xE000;
-                    angle_change_y = 0xCB00;
-                    angle_change_z = 0xB000;
-                },
-                // Progressive Mitts
-                56 | 99 => {
-                    h_offset = 45.0;
-                    angle_change_y = 0xE800;
-                },
-                // Water Dragon Scale | Sea Chart
-                68 | 98 => h_offset = 15.0,
-                // Bug Medal | Life Medal
-                70 | 114 => {
-                    h_offset = 15.0;
-                    angle_change_x = 0x0A80;
-                },
-                // Progressive Bug Net
-                71 | 140 => {
-                    h_offset = 30.0;
-                    angle_change_x = 0x1000;
-                    angle_change_y = 0xE800;
-                    angle_change_z = 0x2000;
-                },
-                // Bomb Bag
-                92 => h_offset = 45.0,
-                // Heart Container | Progressive Pouch | Life Tree Fruit
-                93 | 112 | 113 | 198 => h_offset = 35.0,
-                // Heart Piece
-                94 => h_offset = 40.0,
-                // Triforce Pieces
-                95..=97 => h_offset = 75.0,
-                // Heart Medal | Rupee Medal | Treasure Medal | Potion Medal | Cursed Medal
-                100..=104 => {
-                    h_offset = 15.0;
-                    angle_change_y = 0x4000;
-                    angle_change_z = 0x0A00;
-                },
-                36          // Glittering Spores
-                | 54        // Bottle of Water
-                | 55        // Mushroom Spores
-                | 65        // Guardian Potion
-                | 66        // Guardian Potion+
-                | 74        // Sacred Water
-                | 78        // Heart Potion
-                | 79        // Heart Potion+
-                | 81        // Heart Potion++
-                | 84        // Stamina Potion
-                | 85        // Stamina Potion+
-                | 86        // Air Potion
-                | 87        // Air Potion+
-                | 88        // Fairy in a Bottle
-                | 108..=111 // Wallets
-                | 126       // Revitalizing Potion
-                | 127       // Revitalizing Potion+
-                | 153       // Empty Bottle
-                | 163       // Tumbleweed
-                | 194       // Revitalizing Potion++
-                | 195       // Hot Pumpkin Soup
-                | 196       // Cold Pumpkin Soup
-                | 199 => h_offset = 20.0, // Extra Wallet
-                // Wooden Shield | Hylian Shield
-                116 | 125 => {
-                    h_offset = 25.0;
-                    angle_change_x = 0x0800;
-                    angle_change_y = 0x2400;
-                    angle_change_z = 0x1000;
-                },
-                // Small Quiver
-                131 => {
-                    h_offset = 25.0;
-                    angle_change_x = 0x1000;
-                    angle_change_z = 0x1000;
-                },
-                // Small Bomb Bag
-                134 => h_offset = 30.0,
-                // Fireshield Earrings
-                138 => h_offset = 20.0,
-                // Cawlin's Letter
-                158 => {
-                    h_offset = 15.0;
-                    angle_change_y = 0x2000;
-                },
-                // Beedle's Insect Cage
-                159 => {
-                    h_offset = 40.0;
-                    angle_change_y = 0x2000;
-                },
-                // Rattle
-                160 => {
-                    h_offset = 25.0;
-                    angle_change_y = 0xE000;
-                },
-                // Seeds | All Treasures
-                57 | 60 | 63 | 64 | 165..=176 => h_offset = 25.0,
-                // Tablets
-                177..=179 => {
-                    h_offset = 10.0;
-                    angle_change_x = 0x0800;
-                    angle_change_y = 0x2000;
-                    angle_change_z = 0x0800;
-                },
-                // Stone of Trials
-                180 => {
-                    h_offset = 20.0;
-                    angle_change_x = 0x0800;
-                    angle_change_y = 0x2000;
-                    angle_change_z = 0x0800;
-                },
-                // Small Keys
-                1 | 200..=206 => {
-                    h_offset = 5.0;
-                    angle_change_x = 0x0C00;
-                    angle_change_y = 0x1000;
-                    angle_change_z = 0x0600;
-                },
-                // Maps
-                50 | 207..=213 => {
-                    h_offset = 30.0;
-                    angle_change_x = 0x0800;
-                    angle_change_y = 0x1000;
-                    angle_change_z = 0x0800;
-                },
-                _ => h_offset = 0.0,
-            }
+            let display_info = get_item_display_info((actor_param1 & 0x1FF) as u16);
+            let h_offset = display_info.wall_offset;
 
             // Use trigonometry to figure out the horizontal offsets
             // Assume items are tilted on the x rotation and turned with the
@@ -1064,70 +798,53 @@ pub fn fix_freestanding_item_horizontal_offset(item_actor: *mut dAcItem) {
             (*item_actor).base.members.base.pos.x += xOffset;
             (*item_actor).base.members.base.pos.z += zOffset;
             (*item_actor).base.members.base.rot.x = 0;
-            (*item_actor).base.members.base.rot.x += angle_change_x;
-            (*item_actor).base.members.base.rot.y += angle_change_y;
-            (*item_actor).base.members.base.rot.z += angle_change_z;
+            (*item_actor).base.members.base.rot.x += display_info.wall_rotation_x;
+            (*item_actor).base.members.base.rot.y += display_info.wall_rotation_y;
+            (*item_actor).base.members.base.rot.z += display_info.wall_rotation_z;
         }
     }
 }
 
-#[no_mangle]
-pub fn check_and_open_trial_gates(collected_item: flag::ITEMFLAGS) {
+// Called by the flag observers when the Goddess Harp or a song is collected,
+// and by handle_startflags for the starting items
+pub fn open_trial_gates() {
     unsafe {
         // Don't try to open any trial gates if the setting isn't on
-        if RANDOMIZER_SETTINGS.skip_harp_playing == 0 {
+        let skip_harp_playing = match settings::get_randomizer_settings() {
+            Some(settings) => settings.skip_harp_playing,
+            None => 0,
+        };
+        if skip_harp_playing == 0 {
             return;
         }
 
-        // Return early if we aren't collecting any relevant items
-        let relevant_items = [
-            flag::ITEMFLAGS::GODDESS_HARP,
-            flag::ITEMFLAGS::FARORE_COURAGE,
-            flag::ITEMFLAGS::NAYRU_WISDOM,
-            flag::ITEMFLAGS::DIN_POWER,
-            flag::ITEMFLAGS::FARON_SONG_OF_THE_HERO_PART,
-            flag::ITEMFLAGS::SONG_OF_THE_HERO,
+        // (song, trial gate open sceneflag)
+        let trial_gate_flags = [
+            (
+                flag::ITEMFLAGS::FARORE_COURAGE,
+                flag::FlagRef::sceneflag(1, 17),
+            ),
+            (
+                flag::ITEMFLAGS::NAYRU_WISDOM,
+                flag::FlagRef::sceneflag(7, 91),
+            ),
+            (flag::ITEMFLAGS::DIN_POWER, flag::FlagRef::sceneflag(4, 70)),
+            (
+                flag::ITEMFLAGS::SONG_OF_THE_HERO,
+                flag::FlagRef::sceneflag(0, 69),
+            ),
         ];
-        if !relevant_items.iter().any(|&item| item == collected_item) {
-            return;
-        }
 
         let mut open_trial_gate = false;
         // If we have the Goddess Harp and the appropriate song, set
         // the scene flag for the trial gate being open. If we're in
-        // the scene index where the trial is, set the flag locally
-        // and then try to find the trial gate actor and open it.
+        // the scene index where the trial is, try to find the trial
+        // gate actor and open it.
         if flag::check_itemflag(flag::ITEMFLAGS::GODDESS_HARP) == 1 {
-            if flag::check_itemflag(flag::ITEMFLAGS::FARORE_COURAGE) == 1 {
-                if (*SCENEFLAG_MGR).sceneindex == 1 {
-                    flag::set_local_sceneflag(17);
-                    open_trial_gate = true;
-                } else {
-                    flag::set_global_sceneflag(1, 17);
-                }
-            }
-            if flag::check_itemflag(flag::ITEMFLAGS::NAYRU_WISDOM) == 1 {
-                if (*SCENEFLAG_MGR).sceneindex == 7 {
-                    flag::set_local_sceneflag(91);
-                    open_trial_gate = true;
-                } else {
-                    flag::set_global_sceneflag(7, 91);
-                }
-            }
-            if flag::check_itemflag(flag::ITEMFLAGS::DIN_POWER) == 1 {
-                if (*SCENEFLAG_MGR).sceneindex == 4 {
-                    flag::set_local_sceneflag(70);
-                    open_trial_gate = true;
-                } else {
-                    flag::set_global_sceneflag(4, 70);
-                }
-            }
-            if flag::check_itemflag(flag::ITEMFLAGS::SONG_OF_THE_HERO) == 1 {
-                if (*SCENEFLAG_MGR).sceneindex == 0 {
-                    flag::set_local_sceneflag(69);
-                    open_trial_gate = true;
-                } else {
-                    flag::set_global_sceneflag(0, 69);
+            for (song, gate_flag) in trial_gate_flags {
+                if flag::check_itemflag(song) == 1 {
+                    gate_flag.set();
+                    open_trial_gate |= gate_flag.is_local();
                 }
             }
         }
@@ -1140,7 +857,7 @@ pub fn check_and_open_trial_gates(collected_item: flag::ITEMFLAGS) {
                 actor::find_actor_by_type(actor::ACTORID::OBJ_WARP, core::ptr::null_mut())
                     as *mut actor::dAcOWarp;
             // If it exists, change it's state to open
-            if trial_gate_actor != core::ptr::null_mut() {
+            if !trial_gate_actor.is_null() {
                 ((*(*trial_gate_actor).state_mgr.vtable).change_state)(
                     &mut (*trial_gate_actor).state_mgr as *mut actor::StateMgr,
                     &mut dAcOWarp__StateGateOpen as *mut c_void,
@@ -1154,7 +871,7 @@ pub fn check_and_open_trial_gates(collected_item: flag::ITEMFLAGS) {
 pub fn after_item_collection_hook(collected_item: flag::ITEMFLAGS) -> flag::ITEMFLAGS {
     unsafe {
         fix::fix_ammo_counts(collected_item);
-        check_and_open_trial_gates(collected_item);
+        observer::notify_item_collected(collected_item);
 
         // Replaced code
         asm!("mov w8, {0:w}", in(reg) ((collected_item as u16) - 2));
@@ -1163,71 +880,55 @@ pub fn after_item_collection_hook(collected_item: flag::ITEMFLAGS) -> flag::ITEM
     }
 }
 
-#[no_mangle]
-pub fn resolve_progressive_item_models(model_name: *const c_char, item_id: u16) -> *const c_char {
+// The next stage of a progressive item is the first one that isn't owned yet.
+// Once they're all owned the last stage is used
+pub fn get_progressive_item_stage(base_itemid: u16) -> Option<&'static ProgressiveItemStage> {
     unsafe {
-        match item_id {
-            // Progressive Bow
-            19 => {
-                if flag::check_itemflag(flag::ITEMFLAGS::BOW) == 0 {
-                    return cstr!("GetBowA").as_ptr();
-                }
-                if flag::check_itemflag(flag::ITEMFLAGS::IRON_BOW) == 0 {
-                    return cstr!("GetBowB").as_ptr();
-                }
-                return cstr!("GetBowC").as_ptr();
-            },
-            // Progressive Slingshot
-            52 => {
-                if flag::check_itemflag(flag::ITEMFLAGS::SLINGSHOT) == 0 {
-                    return cstr!("GetPachinkoA").as_ptr();
-                }
-                return cstr!("GetPachinkoB").as_ptr();
-            },
-            // Progressive Beetle
-            53 => {
-                if flag::check_itemflag(flag::ITEMFLAGS::BEETLE) == 0 {
-                    return cstr!("GetBeetleA").as_ptr();
-                }
-                if flag::check_itemflag(flag::ITEMFLAGS::HOOK_BEETLE) == 0 {
-                    return cstr!("GetBeetleB").as_ptr();
-                }
-                if flag::check_itemflag(flag::ITEMFLAGS::QUICK_BEETLE) == 0 {
-                    return cstr!("GetBeetleC").as_ptr();
-                }
-                return cstr!("GetBeetleD").as_ptr();
-            },
-            // Progressive Mitts
-            56 => {
-                if flag::check_itemflag(flag::ITEMFLAGS::DIGGING_MITTS) == 0 {
-                    return cstr!("GetMoleGloveA").as_ptr();
-                }
-                return cstr!("GetMoleGloveB").as_ptr();
-            },
-            // Progressive Bug Net
-            71 => {
-                if flag::check_itemflag(flag::ITEMFLAGS::BUG_NET) == 0 {
-                    return cstr!("GetNetA").as_ptr();
-                }
-                return cstr!("GetNetB").as_ptr();
-            },
-            // Progressive Wallet
-            108 => {
-                if flag::check_itemflag(flag::ITEMFLAGS::MEDIUM_WALLET) == 0 {
-                    return cstr!("GetPurseB").as_ptr();
-                }
-                if flag::check_itemflag(flag::ITEMFLAGS::BIG_WALLET) == 0 {
-                    return cstr!("GetPurseC").as_ptr();
-                }
-                if flag::check_itemflag(flag::ITEMFLAGS::GIANT_WALLET) == 0 {
-                    return cstr!("GetPurseD").as_ptr();
-                }
-                return cstr!("GetPurseE").as_ptr();
-            },
-            _ => {
-                return model_name;
-            },
+        for chain in PROGRESSIVE_ITEM_CHAINS.iter() {
+            if chain.base_itemid == 0 {
+                break;
+            }
+
+            if chain.base_itemid != base_itemid || chain.stage_count == 0 {
+                continue;
+            }
+
+            let stage_count = (chain.stage_count as usize).min(MAX_PROGRESSIVE_ITEM_STAGES);
+            let stages = &chain.stages[..stage_count];
+            return stages
+                .iter()
+                .find(|stage| !stage.flag.check())
+                .or(stages.last());
         }
+
+        return None;
+    }
+}
+
+// Stages without a model keep the one the game picked
+fn get_model_name_ptr(name: &[u8; 16], default: *const c_char) -> *const c_char {
+    if name[0] == 0 {
+        return default;
+    }
+    return name.as_ptr() as *const c_char;
+}
+
+#[no_mangle]
+pub fn resolve_progressive_item_arc_name(arc_name: *const c_char, item_id: u16) -> *const c_char {
+    match get_progressive_item_stage(item_id) {
+        Some(stage) => return get_model_name_ptr(&stage.arc_name, arc_name),
+        None => return arc_name,
+    }
+}
+
+#[no_mangle]
+pub fn resolve_progressive_item_model_name(
+    model_name: *const c_char,
+    item_id: u16,
+) -> *const c_char {
+    match get_progressive_item_stage(item_id) {
+        Some(stage) => return get_model_name_ptr(&stage.model_name, model_name),
+        None => return model_name,
     }
 }
 
@@ -1238,21 +939,12 @@ pub fn get_arc_model_from_item(
     item_id: u16,
 ) -> u64 {
     unsafe {
-        let mut initial_model_name = match item_id {
-            214 => cstr!("Onp").as_ptr(),
-            215 => cstr!("DesertRobot").as_ptr(),
-            _ => model_name,
+        let initial_model_name = match customitem::get_custom_item(item_id) {
+            Some(custom_item) => custom_item.arc_name.as_ptr(),
+            None => model_name,
         };
 
-        if item_id == 112 {
-            if flag::check_itemflag(flag::ITEMFLAGS::ADVENTURE_POUCH) == 0 {
-                initial_model_name = cstr!("GetPouchA").as_ptr();
-            } else {
-                initial_model_name = cstr!("GetPouchB").as_ptr();
-            }
-        }
-
-        let resolved_model_name = resolve_progressive_item_models(initial_model_name, item_id);
+        let resolved_model_name = resolve_progressive_item_arc_name(initial_model_name, item_id);
 
         return getArcModelFromName(
             arc_table,
@@ -1265,21 +957,12 @@ pub fn get_arc_model_from_item(
 #[no_mangle]
 pub fn get_item_model_name_ptr(model_name: *const c_char, item_id: u16) -> *const c_char {
     unsafe {
-        let mut initial_model_name = match item_id {
-            214 => cstr!("OnpB").as_ptr(),
-            215 => cstr!("DesertRobot").as_ptr(),
-            _ => model_name,
+        let initial_model_name = match customitem::get_custom_item(item_id) {
+            Some(custom_item) => custom_item.model_name.as_ptr(),
+            None => model_name,
         };
 
-        if item_id == 112 {
-            if flag::check_itemflag(flag::ITEMFLAGS::ADVENTURE_POUCH) == 0 {
-                initial_model_name = cstr!("GetPorchA").as_ptr();
-            } else {
-                initial_model_name = cstr!("GetPorchB").as_ptr();
-            }
-        }
-
-        let resolved_model_name = resolve_progressive_item_models(initial_model_name, item_id);
+        let resolved_model_name = resolve_progressive_item_model_name(initial_model_name, item_id);
 
         // Replaced code
         asm!("mov x1, {0:x}", in(reg) item_id);
@@ -1293,11 +976,7 @@ pub fn get_item_model_name_ptr(model_name: *const c_char, item_id: u16) -> *cons
 #[no_mangle]
 pub fn change_model_scale(item_actor: *mut dAcItem, world_matrix: *const c_void) {
     unsafe {
-        let mut scale = match (*item_actor).final_determined_itemid {
-            214 => 0.5f32, // Tadtone
-            215 => 0.3f32, // Scrapper
-            _ => 1.0f32,
-        };
+        let scale = get_item_display_info((*item_actor).final_determined_itemid).model_scale;
 
         (*item_actor).base.members.base.scale.x *= scale;
         (*item_actor).base.members.base.scale.y *= scale;
@@ -1358,11 +1037,45 @@ pub fn get_custom_freestanding_item_scale() -> f32 {
             return 1.0;
         }
 
-        return match (*item_actor).final_determined_itemid {
-            40 => 1.5, // 5 Bombs
-            // Tumbleweed, Ancient Flower, Blue Bird Feather, Goddess Plume
-            60 | 163 | 166 | 174 | 176 => 2.0,
-            _ => 1.0,
-        };
+        return get_item_display_info((*item_actor).final_determined_itemid).freestanding_scale;
+    }
+}
+
+pub fn get_dungeon_item(itemid: u16) -> Option<&'static DungeonItem> {
+    unsafe {
+        for dungeon_item in DUNGEON_ITEMS.iter() {
+            if dungeon_item.itemid == 0 {
+                break;
+            }
+
+            if dungeon_item.itemid == itemid {
+                return Some(dungeon_item);
+            }
+        }
+
+        return None;
+    }
+}
+
+// Dungeonflags update the live flags too if the item is collected in its own
+// dungeon
+pub fn give_dungeon_item(dungeon_item: &DungeonItem) {
+    let flag = dungeon_item.flag;
+    let amount = dungeon_item.amount;
+
+    if amount == 0 {
+        flag.set();
+        return;
+    }
+
+    match flag.space {
+        flag::FLAG_SPACE::DUNGEONFLAG => {
+            flag::add_to_dungeonflag_counter(
+                flag.sceneindex.into(),
+                flag.flag.into(),
+                amount.into(),
+            );
+        },
+        _ => flag.set_counter(flag.get_counter().saturating_add(amount.into())),
     }
 }
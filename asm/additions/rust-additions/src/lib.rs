@@ -14,21 +14,27 @@ use static_assertions::assert_eq_size;
 mod actor;
 mod ammo;
 mod color;
+mod customitem;
 mod debug;
 mod entrance;
 mod event;
 mod fix;
 mod flag;
+#[cfg(feature = "debug")]
+mod flagdiff;
 mod input;
 mod item;
 mod lyt;
 mod mainloop;
 mod math;
 mod minigame;
+mod multiworld;
+mod observer;
 mod player;
 mod rng;
 mod savefile;
 mod settings;
+mod tracker;
 mod traps;
 
 // repr(C) prevents rust from reordering struct fields.
@@ -5,7 +5,12 @@
 use crate::color;
 use crate::debug;
 use crate::fix;
+#[cfg(feature = "debug")]
+use crate::flagdiff;
 use crate::input;
+use crate::multiworld;
+use crate::settings;
+use crate::tracker;
 
 use core::arch::asm;
 use core::ffi::{c_char, c_void};
@@ -55,21 +60,28 @@ extern "C" {
 #[no_mangle]
 pub fn main_loop_inject() -> *mut c_void {
     unsafe {
+        input::update_button_combos();
+
         // Soft-reset button combo
-        if (input::check_button_held(input::BUTTON_INPUTS::LEFT_STICK_BUTTON)
-            && input::check_button_held(input::BUTTON_INPUTS::A_BUTTON)
-            && input::check_button_held(input::BUTTON_INPUTS::R_BUTTON))
-        {
+        if input::check_button_combo_held(settings::SOFT_RESET_COMBO) {
             (*reload_color_fader).other_state = 1;
             (*reload_color_fader).previous_state = (*reload_color_fader).current_state;
             (*reload_color_fader).current_state = 1;
             do_soft_reset(reload_color_fader);
         }
 
+        tracker::update_location_log_frame();
+        tracker::update_tracker_state();
+
         color::handle_colors();
 
         fix::apply_loftwing_speed_override();
 
+        multiworld::process_multiworld_mailbox();
+
+        #[cfg(feature = "debug")]
+        flagdiff::update_flag_snapshot();
+
         return dSystem;
     }
 }
@@ -78,7 +90,7 @@ pub fn main_loop_inject() -> *mut c_void {
 pub fn activate_back_in_time(param1: *mut c_void) -> *mut c_void {
     // This is patched into the do_soft_reset function
     unsafe {
-        if input::check_button_held(input::BUTTON_INPUTS::L_BUTTON) {
+        if input::check_button_combo_held(settings::BACK_IN_TIME_COMBO) {
             RESPAWN_TYPE = 3;
         }
 
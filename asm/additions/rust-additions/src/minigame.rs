@@ -50,6 +50,10 @@ impl MinigameState {
     pub fn is_current(self) -> bool {
         Self::get() == self
     }
+
+    pub fn is_any_active() -> bool {
+        Self::get() > Self::State0
+    }
 }
 
 // IMPORTANT: when using vanilla code, the start point must be declared in
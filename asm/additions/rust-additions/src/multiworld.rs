@@ -0,0 +1,209 @@
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(unused)]
+
+use crate::actor;
+use crate::debug;
+use crate::event;
+use crate::item;
+use crate::minigame;
+use crate::player;
+use crate::savefile;
+
+use core::arch::asm;
+use core::ffi::{c_char, c_void};
+use cstr::cstr;
+use static_assertions::assert_eq_size;
+
+// repr(C) prevents rust from reordering struct fields.
+// packed(1) prevents rust from aligning structs to the size of the largest
+// field.
+
+// Using u64 or 64bit pointers forces structs to be 8-byte aligned.
+// The vanilla code seems to be 4-byte aligned. To make extra sure, used
+// packed(1) to force the alignment to match what you define.
+
+// Always add an assert_eq_size!() macro after defining a struct to ensure it's
+// the size you expect it to be.
+
+// Multiworld mailbox
+//
+// An external client (e.g. an emulator memory bridge) pushes received items
+// into this mailbox. Every item has an absolute index which counts up from 0
+// for the whole seed. The number of items that have already been given is
+// stored in the save file so reloading a file never gives an item twice.
+//
+// Protocol (client side):
+// 1. Wait for the magic and version to match.
+// 2. To send item n, write entries[n % MAILBOX_CAPACITY] with index = n and
+//    only then set write_index = n + 1. Never write more than MAILBOX_CAPACITY
+//    items past read_index.
+// 3. If read_index goes backwards (the player reloaded an older save), resend
+//    everything from read_index onwards.
+// 4. If status is INVALID_ITEM, the entry at rejected_index wasn't given and
+//    won't be until the client writes a valid item to it.
+//
+// See util/multiworld.py for a host-side mock of both ends.
+pub const MAILBOX_MAGIC: u32 = 0x424D574D; // "MWMB" in memory
+pub const MAILBOX_VERSION: u16 = 2;
+pub const MAILBOX_CAPACITY: usize = 32;
+
+// Written by the game every frame the mailbox is processed
+#[repr(u16)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum MAILBOX_STATUS {
+    OK           = 0,
+    // The save file space for the randomizer's data is in use by something
+    // else, so nothing can be received on this file
+    NO_SAVE_DATA = 1,
+    // The entry at rejected_index has an itemid that doesn't exist
+    INVALID_ITEM = 2,
+}
+
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct MailboxEntry {
+    pub index:  u32,
+    pub itemid: u16,
+    pub sender: u16, // world the item came from (informational only)
+}
+assert_eq_size!([u8; 8], MailboxEntry);
+
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct MultiworldMailbox {
+    pub magic:          u32,
+    pub version:        u16,
+    pub capacity:       u16,
+    pub write_index:    u32,            // written by the client
+    pub read_index:     u32,            // written by the game
+    pub status:         MAILBOX_STATUS, // written by the game
+    pub _0:             [u8; 2],
+    pub rejected_index: u32, // written by the game
+    pub entries:        [MailboxEntry; MAILBOX_CAPACITY],
+}
+assert_eq_size!([u8; 0x118], MultiworldMailbox);
+
+// IMPORTANT: when using vanilla code, the start point must be declared in
+// symbols.yaml and then added to this extern block.
+extern "C" {
+    static PLAYER_PTR: *mut player::dPlayer;
+
+    static GAME_RELOADER_PTR: *mut actor::GameReloader;
+
+    static mut CURRENT_LAYER: u8;
+
+    // Custom symbols
+    static mut MULTIWORLD_MAILBOX: MultiworldMailbox;
+
+    // Functions
+    fn debugPrint_128(string: *const c_char, fstr: *const c_char, ...);
+}
+
+// IMPORTANT: when adding functions here that need to get called from the game,
+// add `#[no_mangle]` and add a .global *symbolname* to
+// additions/rust-additions.asm
+
+#[no_mangle]
+pub fn can_receive_items() -> bool {
+    unsafe {
+        // No player on the title screen (layer 28) or while reloading
+        if PLAYER_PTR.is_null() || CURRENT_LAYER == 28 || (*GAME_RELOADER_PTR).is_reloading != 0 {
+            return false;
+        }
+
+        if event::is_in_event() || minigame::MinigameState::is_any_active() {
+            return false;
+        }
+
+        // Actions where an item get would either be ignored or softlock
+        match (*PLAYER_PTR).current_action {
+            player::PLAYER_ACTIONS::DIVE_SKY
+            | player::PLAYER_ACTIONS::FREE_FALL
+            | player::PLAYER_ACTIONS::USING_SAILCLOTH
+            | player::PLAYER_ACTIONS::FORCE_SAILCLOTH_VENT
+            | player::PLAYER_ACTIONS::HANGING_ON_LEDGE
+            | player::PLAYER_ACTIONS::MOVE_ON_LEDGE
+            | player::PLAYER_ACTIONS::MOVE_ON_VINES
+            | player::PLAYER_ACTIONS::IDLE_ON_VINES
+            | player::PLAYER_ACTIONS::CLIMB_LADDER
+            | player::PLAYER_ACTIONS::CLIMB_UP_VINE_ROPE
+            | player::PLAYER_ACTIONS::CLIMB_DOWN_VINE_ROPE
+            | player::PLAYER_ACTIONS::WALKING_ON_TIGHTROPE
+            | player::PLAYER_ACTIONS::HANGING_ON_ZIP
+            | player::PLAYER_ACTIONS::USE_CLAWSHOTS
+            | player::PLAYER_ACTIONS::BEING_PULLED_BY_CLAWS
+            | player::PLAYER_ACTIONS::HANG_FROM_PEAHAT
+            | player::PLAYER_ACTIONS::HANG_FROM_TARGET
+            | player::PLAYER_ACTIONS::IN_TRUCK_MINECART
+            | player::PLAYER_ACTIONS::ON_BIRD
+            | player::PLAYER_ACTIONS::IN_BOAT
+            | player::PLAYER_ACTIONS::IN_GROOSENATOR
+            | player::PLAYER_ACTIONS::ITEM_GET
+            | player::PLAYER_ACTIONS::OPEN_CHEST
+            | player::PLAYER_ACTIONS::DIE
+            | player::PLAYER_ACTIONS::VOID_SAND
+            | player::PLAYER_ACTIONS::DAMAGE_LAVA => return false,
+            _ => return true,
+        }
+    }
+}
+
+// Gives at most one item per frame. The item get itself starts an event, so
+// the next item waits until the textbox has been closed.
+#[no_mangle]
+pub fn process_multiworld_mailbox() {
+    unsafe {
+        let mailbox = &mut *core::ptr::addr_of_mut!(MULTIWORLD_MAILBOX);
+
+        // Don't read anything if the header doesn't match this build
+        if mailbox.magic != MAILBOX_MAGIC || mailbox.version != MAILBOX_VERSION {
+            return;
+        }
+
+        // Wait until there's somewhere to keep count of the items given
+        let rando = match savefile::get_rando_save_data() {
+            Some(rando) => rando,
+            None => {
+                mailbox.status = MAILBOX_STATUS::NO_SAVE_DATA;
+                return;
+            },
+        };
+
+        let received = rando.multiworld_items_received;
+        mailbox.read_index = received;
+
+        if mailbox.write_index <= received {
+            mailbox.status = MAILBOX_STATUS::OK;
+            return;
+        }
+
+        // The client writes the entry before bumping write_index, but make sure
+        // this slot isn't left over from a previous lap of the ring buffer
+        let entry = mailbox.entries[received as usize % MAILBOX_CAPACITY];
+        if entry.index != received {
+            return;
+        }
+
+        // Leave invalid entries unread so the client can replace them
+        if entry.itemid > u8::MAX as u16 {
+            mailbox.status = MAILBOX_STATUS::INVALID_ITEM;
+            mailbox.rejected_index = received;
+            return;
+        }
+
+        mailbox.status = MAILBOX_STATUS::OK;
+
+        if !can_receive_items() {
+            return;
+        }
+
+        // Try again next frame if the item couldn't be spawned
+        if item::give_item_with_sceneflag(entry.itemid as u8, 0xFF).is_null() {
+            return;
+        }
+
+        rando.multiworld_items_received = received + 1;
+        mailbox.read_index = received + 1;
+    }
+}
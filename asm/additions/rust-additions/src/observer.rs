@@ -0,0 +1,136 @@
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(unused)]
+
+use crate::debug;
+use crate::flag::{FlagRef, ITEMFLAGS};
+use crate::item;
+
+use core::arch::asm;
+use core::ffi::{c_char, c_void};
+use cstr::cstr;
+use static_assertions::assert_eq_size;
+
+// repr(C) prevents rust from reordering struct fields.
+// packed(1) prevents rust from aligning structs to the size of the largest
+// field.
+
+// Using u64 or 64bit pointers forces structs to be 8-byte aligned.
+// The vanilla code seems to be 4-byte aligned. To make extra sure, used
+// packed(1) to force the alignment to match what you define.
+
+// Always add an assert_eq_size!() macro after defining a struct to ensure it's
+// the size you expect it to be.
+
+//////////////////////
+// ADD STRUCTS HERE //
+//////////////////////
+
+// Runs `callback` when `flag` becomes set (or unset if `on_set` is false).
+// Only changes made through FlagRef and the set/unset functions in flag.rs are
+// seen, along with items being collected. When the game changes a flag through
+// its own flag managers the observers aren't told, so a reaction to one of
+// those needs a patch point that goes through flag.rs (like the day/night and
+// Stone of Trials storyflags do). Startflags aren't seen either, so anything
+// added here that should also react to the starting flags has to be run from
+// handle_startflags too. Callbacks may still run more than once for the same
+// change and must be safe to repeat
+#[derive(Copy, Clone)]
+pub struct FlagObserver {
+    pub flag:     FlagRef,
+    pub on_set:   bool,
+    pub callback: fn(),
+}
+
+impl FlagObserver {
+    pub const fn on_set(flag: FlagRef, callback: fn()) -> FlagObserver {
+        return FlagObserver {
+            flag,
+            on_set: true,
+            callback,
+        };
+    }
+
+    pub const fn on_unset(flag: FlagRef, callback: fn()) -> FlagObserver {
+        return FlagObserver {
+            flag,
+            on_set: false,
+            callback,
+        };
+    }
+}
+
+// Add new reactions to flag changes here
+static FLAG_OBSERVERS: [FlagObserver; 6] = [
+    // Open the trial gates once the Goddess Harp and the trial's song are owned
+    FlagObserver::on_set(
+        FlagRef::itemflag(ITEMFLAGS::GODDESS_HARP),
+        item::open_trial_gates,
+    ),
+    FlagObserver::on_set(
+        FlagRef::itemflag(ITEMFLAGS::FARORE_COURAGE),
+        item::open_trial_gates,
+    ),
+    FlagObserver::on_set(
+        FlagRef::itemflag(ITEMFLAGS::NAYRU_WISDOM),
+        item::open_trial_gates,
+    ),
+    FlagObserver::on_set(
+        FlagRef::itemflag(ITEMFLAGS::DIN_POWER),
+        item::open_trial_gates,
+    ),
+    FlagObserver::on_set(
+        FlagRef::itemflag(ITEMFLAGS::FARON_SONG_OF_THE_HERO_PART),
+        item::open_trial_gates,
+    ),
+    FlagObserver::on_set(
+        FlagRef::itemflag(ITEMFLAGS::SONG_OF_THE_HERO),
+        item::open_trial_gates,
+    ),
+];
+
+// Callbacks can change flags themselves, this stops them from setting each
+// other off forever
+const MAX_OBSERVER_DEPTH: u8 = 4;
+static mut OBSERVER_DEPTH: u8 = 0;
+
+// IMPORTANT: when using vanilla code, the start point must be declared in
+// symbols.yaml and then added to this extern block.
+extern "C" {
+    // Functions
+    fn debugPrint_128(string: *const c_char, fstr: *const c_char, ...);
+}
+
+// IMPORTANT: when adding functions here that need to get called from the game,
+// add `#[no_mangle]` and add a .global *symbolname* to
+// additions/rust-additions.asm
+
+pub fn notify_flag_change(flag: FlagRef, was_set: bool, is_set: bool) {
+    if was_set == is_set {
+        return;
+    }
+
+    unsafe {
+        if OBSERVER_DEPTH >= MAX_OBSERVER_DEPTH {
+            debug::debug_print_num(
+                cstr!("Skipping flag observers, too deep: %d").as_ptr(),
+                flag.flag as usize,
+            );
+            return;
+        }
+
+        OBSERVER_DEPTH += 1;
+        for observer in FLAG_OBSERVERS.iter() {
+            if observer.flag == flag && observer.on_set == is_set {
+                (observer.callback)();
+            }
+        }
+        OBSERVER_DEPTH -= 1;
+    }
+}
+
+// The game sets the itemflag itself before after_item_collection_hook runs, so
+// collecting an item always counts as the itemflag becoming set
+pub fn notify_item_collected(collected_item: ITEMFLAGS) {
+    notify_flag_change(FlagRef::itemflag(collected_item), false, true);
+}
@@ -3,6 +3,8 @@
 #![allow(unused)]
 
 use crate::debug;
+use crate::entrance;
+use crate::flag;
 use crate::math;
 
 use core::arch::asm;
@@ -53,7 +55,8 @@ pub struct SaveFile {
     pub storyflags:                [u16; 128],
     pub itemflags:                 [u16; 64],
     pub dungeonflags:              [[u16; 8]; 26],
-    pub _1:                        [u8; 3680],
+    pub _1:                        [u8; 2144],
+    pub rando:                     RandoSaveData,
     pub sceneflags:                [[u16; 8]; 26],
     pub _2:                        [u8; 4960],
     pub enemy_kill_counters:       [u16; 100],
@@ -88,9 +91,36 @@ pub struct SaveFile {
 }
 assert_eq_size!([u8; 21440], SaveFile);
 
+// Randomizer data that needs to persist with the file. It lives at the end of
+// the unknown block after the dungeonflags. Nothing is known to read or write
+// that space but it hasn't been confirmed with save diffs yet (see
+// util/savefile.py), so get_rando_save_data only claims it while it's still
+// zeroed and won't touch it if it holds anything else. The multiworld
+// mailbox reports that as MAILBOX_STATUS::NO_SAVE_DATA so clients can tell the
+// player.
+// Anything added here must shrink the trailing padding to keep the size the
+// same.
+pub const RANDO_SAVE_DATA_MAGIC: u32 = 0x56415352; // "RSAV" in memory
+pub const RANDO_SAVE_DATA_VERSION: u16 = 1;
+
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct RandoSaveData {
+    pub magic:                     u32,
+    pub version:                   u16,
+    pub _0:                        [u8; 2],
+    pub multiworld_items_received: u32,
+    pub entrance_history:          entrance::EntranceHistory,
+    pub location_flags:            [u16; flag::LOCATION_FLAG_WORDS],
+    pub _1:                        [u8; 240],
+}
+assert_eq_size!([u8; 0x600], RandoSaveData);
+
 // IMPORTANT: when using vanilla code, the start point must be declared in
 // symbols.yaml and then added to this extern block.
 extern "C" {
+    static FILE_MGR: *mut FileMgr;
+
     // Functions
     fn debugPrint_128(string: *const c_char, fstr: *const c_char, ...);
 }
@@ -102,3 +132,26 @@ extern "C" {
 ////////////////////////
 // ADD FUNCTIONS HERE //
 ////////////////////////
+
+// Returns the randomizer data of the current file, or None if the space is
+// in use by something else
+pub fn get_rando_save_data() -> Option<&'static mut RandoSaveData> {
+    unsafe {
+        let rando = &mut (*FILE_MGR).FA.rando;
+
+        if rando.magic != RANDO_SAVE_DATA_MAGIC || rando.version != RANDO_SAVE_DATA_VERSION {
+            let bytes = core::slice::from_raw_parts(
+                rando as *const RandoSaveData as *const u8,
+                core::mem::size_of::<RandoSaveData>(),
+            );
+            if bytes.iter().any(|byte| *byte != 0) {
+                return None;
+            }
+
+            rando.magic = RANDO_SAVE_DATA_MAGIC;
+            rando.version = RANDO_SAVE_DATA_VERSION;
+        }
+
+        return Some(rando);
+    }
+}
@@ -20,16 +20,49 @@ use static_assertions::assert_eq_size;
 // Always add an assert_eq_size!() macro after defining a struct to ensure it's
 // the size you expect it to be.
 
+// Indices into RandomizerSettings.button_combos
+pub const SOFT_RESET_COMBO: usize = 0;
+pub const BACK_IN_TIME_COMBO: usize = 1;
+
+// A combo is held once all of `buttons` (input::BUTTON_INPUTS ORed together)
+// have been held down for more than `hold_frames` frames
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct ButtonCombo {
+    pub buttons:     u32,
+    pub hold_frames: u16,
+    pub enabled:     u8,
+    pub _0:          u8,
+}
+assert_eq_size!([u8; 8], ButtonCombo);
+
+// BEGIN GENERATED RANDOMIZER SETTINGS
+// Generated from data/runtime_settings.yaml by util/runtime_settings.py,
+// don't edit by hand.
+pub const RANDOMIZER_SETTINGS_MAGIC: u32 = 0x54455352; // "RSET" in memory
+pub const RANDOMIZER_SETTINGS_VERSION: u16 = 1;
+pub const SKY_CLOUD_COLORS_COUNT: usize = 4;
+pub const BUTTON_COMBOS_COUNT: usize = 2;
+
 #[repr(C, packed(1))]
 #[derive(Copy, Clone)]
 pub struct RandomizerSettings {
+    pub magic:             u32,
+    pub version:           u16,
+    pub size:              u16,
+    pub sky_cloud_colors:  [u8; SKY_CLOUD_COLORS_COUNT],
     pub skip_harp_playing: u8,
+    pub _0:                [u8; 3],
+    pub button_combos:     [ButtonCombo; BUTTON_COMBOS_COUNT],
 }
-assert_eq_size!([u8; 0x1], RandomizerSettings);
+assert_eq_size!([u8; 0x20], RandomizerSettings);
+// END GENERATED RANDOMIZER SETTINGS
 
 // IMPORTANT: when using vanilla code, the start point must be declared in
 // symbols.yaml and then added to this extern block.
 extern "C" {
+    static RANDOMIZER_SETTINGS: RandomizerSettings;
+
     // Functions
     fn debugPrint_128(string: *const c_char, fstr: *const c_char, ...);
 }
@@ -38,6 +71,19 @@ extern "C" {
 // add `#[no_mangle]` and add a .global *symbolname* to
 // additions/rust-additions.asm
 
-////////////////////////
-// ADD FUNCTIONS HERE //
-////////////////////////
+// Returns None if the patcher wrote settings this build can't read (e.g. a
+// newer version), callers should fall back to the vanilla behavior
+pub fn get_randomizer_settings() -> Option<&'static RandomizerSettings> {
+    unsafe {
+        let settings = &*(&RANDOMIZER_SETTINGS as *const RandomizerSettings);
+
+        if settings.magic != RANDOMIZER_SETTINGS_MAGIC
+            || settings.version != RANDOMIZER_SETTINGS_VERSION
+            || settings.size as usize != core::mem::size_of::<RandomizerSettings>()
+        {
+            return None;
+        }
+
+        return Some(settings);
+    }
+}
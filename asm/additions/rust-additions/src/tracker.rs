@@ -0,0 +1,298 @@
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(unused)]
+
+use crate::debug;
+use crate::event;
+use crate::flag;
+use crate::minigame;
+use crate::player;
+use crate::savefile;
+
+use core::arch::asm;
+use core::ffi::{c_char, c_void};
+use cstr::cstr;
+use static_assertions::assert_eq_size;
+
+// repr(C) prevents rust from reordering struct fields.
+// packed(1) prevents rust from aligning structs to the size of the largest
+// field.
+
+// Using u64 or 64bit pointers forces structs to be 8-byte aligned.
+// The vanilla code seems to be 4-byte aligned. To make extra sure, used
+// packed(1) to force the alignment to match what you define.
+
+// Always add an assert_eq_size!() macro after defining a struct to ensure it's
+// the size you expect it to be.
+
+// Location check log
+//
+// Every time a randomized location is collected, a record is appended to this
+// ring buffer so trackers and multiworld clients don't have to diff flags
+// (which breaks for locations that share or reuse flags).
+//
+// Protocol (reader side):
+// 1. Wait for the magic and version to match.
+// 2. Keep your own cursor, starting at 0. While cursor < write_cursor, read
+//    records[cursor % LOCATION_LOG_CAPACITY] and increment the cursor.
+// 3. If write_cursor - cursor > LOCATION_LOG_CAPACITY, records were
+//    overwritten before they could be read and the reader should resync from
+//    the flags instead.
+// 4. If write_cursor goes backwards, the game has been restarted.
+//
+// The game writes the record before bumping write_cursor.
+// See util/tracker.py for a host-side reader.
+pub const LOCATION_LOG_MAGIC: u32 = 0x474C434C; // "LCLG" in memory
+pub const LOCATION_LOG_VERSION: u16 = 1;
+pub const LOCATION_LOG_CAPACITY: usize = 64;
+
+// Location ids are (kind << 24) | (sceneindex << 16) | flag, except for
+// EVENT ids which are (kind << 24) | (event file number << 12) | flow index
+#[repr(u8)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum LOCATION_KIND {
+    SCENEFLAG     = 0,
+    DUNGEONFLAG   = 1,
+    CHEST         = 2, // flag is the chestflag
+    EVENT         = 3, // see event_location_id
+    LOCATION_FLAG = 4, // flag is the location flag id, the scene is unused
+}
+
+// Sceneflags of the randomized locations whose items keep a sceneflag of their
+// own, so vanilla items with sceneflags aren't logged. Written by the patcher,
+// the table ends with an empty stage name
+pub const MAX_RANDOMIZED_SCENEFLAGS: usize = 256;
+
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct RandomizedSceneflag {
+    pub stage:     [u8; 8],
+    pub sceneflag: u8,
+    pub _0:        u8,
+}
+assert_eq_size!([u8; 10], RandomizedSceneflag);
+
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct LocationCheck {
+    pub location_id: u32,
+    pub itemid:      u16,
+    pub _0:          [u8; 2],
+    pub frame:       u32,
+}
+assert_eq_size!([u8; 12], LocationCheck);
+
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct LocationLog {
+    pub magic:        u32,
+    pub version:      u16,
+    pub capacity:     u16,
+    pub write_cursor: u32,
+    pub frame:        u32, // counts up once per frame from the main loop
+    pub records:      [LocationCheck; LOCATION_LOG_CAPACITY],
+}
+assert_eq_size!([u8; 0x310], LocationLog);
+
+// Tracker state
+//
+// A snapshot of the game state trackers care about, refreshed every frame from
+// the main loop. Trackers should read this instead of poking at vanilla
+// structs whose offsets change between game versions.
+//
+// The game bumps `sequence` before and after every refresh, so a reader should
+// read the sequence, then the block, then the sequence again and retry if the
+// two values differ or are odd.
+//
+// New fields must only be added at the end (in place of the padding) and the
+// version bumped. See util/tracker.py for a host-side reader.
+pub const TRACKER_STATE_MAGIC: u32 = 0x534B5254; // "TRKS" in memory
+pub const TRACKER_STATE_VERSION: u16 = 1;
+
+#[repr(C, packed(1))]
+#[derive(Copy, Clone)]
+pub struct TrackerState {
+    pub magic:              u32,
+    pub version:            u16,
+    pub size:               u16,
+    pub sequence:           u32,
+    pub stage_name:         [u8; 8],
+    pub room:               u8,
+    pub layer:              u8,
+    pub entrance:           u8,
+    pub night:              u8,
+    pub sword:              u8,
+    pub in_game:            bool, // false on the title screen, the rest is stale
+    pub _0:                 [u8; 2],
+    pub health:             u16,
+    pub health_capacity:    u16,
+    pub stamina:            u32,
+    pub minigame:           minigame::MinigameState,
+    pub pouch_items:        [i32; 8],
+    pub dungeon_item_flags: [u8; 26], // boss key (0x80) and map (0x02) per scene
+    pub small_keys:         [u8; 26],
+    pub _1:                 [u8; 4],
+}
+assert_eq_size!([u8; 0x80], TrackerState);
+
+// IMPORTANT: when using vanilla code, the start point must be declared in
+// symbols.yaml and then added to this extern block.
+extern "C" {
+    static PLAYER_PTR: *mut player::dPlayer;
+
+    static FILE_MGR: *mut savefile::FileMgr;
+    static SCENEFLAG_MGR: *mut flag::SceneflagMgr;
+
+    static CURRENT_STAGE_NAME: [u8; 8];
+    static CURRENT_ROOM: u8;
+    static CURRENT_LAYER: u8;
+    static CURRENT_ENTRANCE: u8;
+    static CURRENT_NIGHT: u8;
+
+    static EQUIPPED_SWORD: u8;
+
+    // Custom symbols
+    static RANDOMIZED_SCENEFLAGS: [RandomizedSceneflag; MAX_RANDOMIZED_SCENEFLAGS];
+
+    static mut LOCATION_LOG: LocationLog;
+    static mut EVENT_LOCATION_ID: u32;
+    static mut TRACKER_STATE: TrackerState;
+
+    // Functions
+    fn debugPrint_128(string: *const c_char, fstr: *const c_char, ...);
+}
+
+// IMPORTANT: when adding functions here that need to get called from the game,
+// add `#[no_mangle]` and add a .global *symbolname* to
+// additions/rust-additions.asm
+
+pub fn location_id(kind: LOCATION_KIND, sceneindex: u16, flag: u16) -> u32 {
+    return ((kind as u32) << 24) | ((sceneindex as u32 & 0xFF) << 16) | (flag as u32);
+}
+
+// For locations using a flag in the current scene
+pub fn local_location_id(kind: LOCATION_KIND, flag: u16) -> u32 {
+    unsafe {
+        return location_id(kind, (*SCENEFLAG_MGR).sceneindex, flag);
+    }
+}
+
+#[no_mangle]
+pub fn update_location_log_frame() {
+    unsafe {
+        let log = &mut *core::ptr::addr_of_mut!(LOCATION_LOG);
+        log.frame = log.frame.wrapping_add(1);
+    }
+}
+
+#[no_mangle]
+pub fn log_location_check(location_id: u32, itemid: u16) {
+    unsafe {
+        let log = &mut *core::ptr::addr_of_mut!(LOCATION_LOG);
+
+        // Readers will ignore the log anyway if the header doesn't match
+        if log.magic != LOCATION_LOG_MAGIC || log.version != LOCATION_LOG_VERSION {
+            return;
+        }
+
+        let cursor = log.write_cursor;
+        log.records[cursor as usize % LOCATION_LOG_CAPACITY] = LocationCheck {
+            location_id,
+            itemid,
+            _0: [0; 2],
+            frame: log.frame,
+        };
+        log.write_cursor = cursor.wrapping_add(1);
+    }
+}
+
+// Flow indexes are only unique within an event file so EVENT ids also have
+// the number its name starts with (e.g. 114 for 114-Friend)
+pub fn event_location_id(event_file: u16, flow_index: u16) -> u32 {
+    return ((LOCATION_KIND::EVENT as u32) << 24)
+        | ((event_file as u32 & 0xFFF) << 12)
+        | (flow_index as u32 & 0xFFF);
+}
+
+pub fn is_randomized_sceneflag(sceneflag: u8) -> bool {
+    unsafe {
+        for randomized_sceneflag in RANDOMIZED_SCENEFLAGS.iter() {
+            if randomized_sceneflag.stage[0] == 0 {
+                break;
+            }
+
+            if randomized_sceneflag.sceneflag == sceneflag
+                && randomized_sceneflag.stage == CURRENT_STAGE_NAME
+            {
+                return true;
+            }
+        }
+
+        return false;
+    }
+}
+
+// The patcher puts a command in front of every give item command it patches
+// for a location (see eventpatchhandler.py) which calls this
+pub fn set_event_location(event_file: u16, flow_index: u16) {
+    unsafe {
+        EVENT_LOCATION_ID = event_location_id(event_file, flow_index);
+    }
+}
+
+// Called for every give item command. Vanilla ones are skipped as nothing has
+// set their location beforehand
+#[no_mangle]
+pub fn log_event_location_check(itemid: u16) {
+    unsafe {
+        if EVENT_LOCATION_ID == 0 {
+            return;
+        }
+
+        log_location_check(EVENT_LOCATION_ID, itemid);
+        EVENT_LOCATION_ID = 0;
+    }
+}
+
+#[no_mangle]
+pub fn update_tracker_state() {
+    unsafe {
+        let state = &mut *core::ptr::addr_of_mut!(TRACKER_STATE);
+
+        if state.magic != TRACKER_STATE_MAGIC || state.version != TRACKER_STATE_VERSION {
+            return;
+        }
+
+        state.sequence = state.sequence.wrapping_add(1);
+
+        state.stage_name = CURRENT_STAGE_NAME;
+        state.room = CURRENT_ROOM;
+        state.layer = CURRENT_LAYER;
+        state.entrance = CURRENT_ENTRANCE;
+        state.night = CURRENT_NIGHT;
+
+        // No player on the title screen (layer 28)
+        state.in_game = !PLAYER_PTR.is_null() && CURRENT_LAYER != 28;
+
+        if state.in_game {
+            let file = &(*FILE_MGR).FA;
+
+            state.sword = EQUIPPED_SWORD;
+            state.health = file.current_health;
+            state.health_capacity = file.health_capacity;
+            state.stamina = (*PLAYER_PTR).stamina_amount;
+            state.minigame = minigame::MinigameState::get();
+            state.pouch_items = file.pouch_items;
+
+            // Slot 0 holds the dungeon item flags and slot 1 the small key count
+            for sceneindex in 0..=flag::MAX_SCENEINDEX as usize {
+                state.dungeon_item_flags[sceneindex] =
+                    (flag::get_dungeonflag_counter(sceneindex as u16, 0) & 0xFF) as u8;
+                state.small_keys[sceneindex] =
+                    (flag::get_dungeonflag_counter(sceneindex as u16, 1) & 0xFF) as u8;
+            }
+        }
+
+        state.sequence = state.sequence.wrapping_add(1);
+    }
+}
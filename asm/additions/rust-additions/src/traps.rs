@@ -10,6 +10,7 @@ use crate::item;
 use crate::math;
 use crate::player;
 use crate::savefile;
+use crate::tracker;
 
 use core::arch::asm;
 use core::ffi::{c_char, c_void};
@@ -256,6 +257,8 @@ pub fn npc_traps() {
             NEXT_TRAP_ID = trapid - 1;
         }
 
+        tracker::log_event_location_check(itemid);
+
         // Replaced instructions
         asm!("mov w1, #0x9", "mov w2, {0:w}", in(reg) itemid);
     }
@@ -269,6 +272,14 @@ pub fn fix_tbox_traps() {
 
         let trapid = (((*tbox_actor).base.members.base.param2 >> 28) & 0xF) as u8;
 
+        tracker::log_location_check(
+            tracker::local_location_id(
+                tracker::LOCATION_KIND::CHEST,
+                (*tbox_actor).chestflag as u16,
+            ),
+            (*tbox_actor).itemid_0x1ff as u16,
+        );
+
         if trapid != 0xF {
             // Force a rupoor model (otherwise, you get a trap with a big item get anim and
             // sound)
@@ -11,6 +11,10 @@ import yaml
 # Causes the assembler to print out each instruction it's assembling and it's binary.
 DEBUG_SHOW_ASSEMBLY = False
 
+# Builds the rust additions with the `debug` feature, which logs flag changes
+# after events and stage loads. Never commit diffs built with this on.
+DEBUG_RUST_FEATURES = False
+
 # Yes, these are duplicated in filepathconstants.py
 # This file should NEVER be run as part of the main randomization process.
 # This file should ONLY be run after development changes to asm.
@@ -396,6 +400,14 @@ asm_sdk_paths = list(ASM_SDK_PATH.rglob("*.asm"))
 with temp_dir as temp_dir_name:
     temp_dir_name = Path(temp_dir_name)
 
+    # Generate RandomizerSettings from data/runtime_settings.yaml.
+    print("Generating rust settings")
+    if generate_settings_command := call(
+        [sys.executable, "-m", "util.runtime_settings"],
+        cwd="..",
+    ):
+        raise Exception("Generating rust settings failed.")
+
     # Format rust additions.
     print("Formatting rust code")
     if rust_build_command := call(
@@ -410,7 +422,8 @@ with temp_dir as temp_dir_name:
     # Assemble rust additions.
     print("Building rust code")
     if rust_build_command := call(
-        ["cargo", "build", "--release", "--target=aarch64-unknown-none"],
+        ["cargo", "build", "--release", "--target=aarch64-unknown-none"]
+        + (["--features=debug"] if DEBUG_RUST_FEATURES else []),
         cwd="./additions/rust-additions",
     ):
         raise Exception("Building rust additions failed.")
@@ -20,12 +20,12 @@
 
 .2byte 0xA7 ; Group of Tadtones (214)
 .2byte 0xA7 ; Scrapper          (215)
-.2byte 0xA7 ; Unused            (216)
-.2byte 0xA7 ; Unused            (217)
-.2byte 0xA7 ; Unused            (218)
-.2byte 0xA7 ; Unused            (219)
-.2byte 0xA7 ; Unused            (220)
-.2byte 0xA7 ; Unused            (221)
+.2byte 0    ; SV Key Ring       (216)
+.2byte 0    ; LMF Key Ring      (217)
+.2byte 0    ; AC Key Ring       (218)
+.2byte 0    ; FS Key Ring       (219)
+.2byte 0    ; SSH Key Ring      (220)
+.2byte 0    ; SK Key Ring       (221)
 .2byte 0xA7 ; Unused            (222)
 .2byte 0xA7 ; Unused            (223)
 .2byte 0xA7 ; Unused            (224)
@@ -80,12 +80,12 @@
 .int 0x1000 ; SK Map            (213)
 .int 0      ; Group of Tadtones (214)
 .int 0x1000 ; Scrapper          (215)
-.int 0      ; Unused            (216)
-.int 0      ; Unused            (217)
-.int 0      ; Unused            (218)
-.int 0      ; Unused            (219)
-.int 0      ; Unused            (220)
-.int 0      ; Unused            (221)
+.int 0      ; SV Key Ring       (216)
+.int 0      ; LMF Key Ring      (217)
+.int 0      ; AC Key Ring       (218)
+.int 0      ; FS Key Ring       (219)
+.int 0      ; SSH Key Ring      (220)
+.int 0      ; SK Key Ring       (221)
 .int 0      ; Unused            (222)
 .int 0      ; Unused            (223)
 .int 0      ; Unused            (224)
@@ -24,11 +24,77 @@ SUBSDK8_DATA_START = SUBSDK8_TEXT_START + 0x55A000
 SUBSDK_SIZE = SUBSDK8_TEXT_START - SUBSDK1_START
 
 # Start of .rodata.1 of subsdk1 in Ghidra + size of subsdk1 -> start of .rodata.1 of subsdk8
-SUBSDK_STARTFLAG_OFFSET = 0x712E54B200
-MAX_STARTFLAGS = 1000
-SUBSDK_WARP_TO_START_OFFSET = SUBSDK_STARTFLAG_OFFSET + MAX_STARTFLAGS
-SUBSDK_START_COUNTS_OFFSET = SUBSDK_WARP_TO_START_OFFSET + 12  # Size of spawn info
-SUBSDK_RNG_SEED_OFFSET = SUBSDK_START_COUNTS_OFFSET + 0xC8  # Size of start counts
+# 0x712E54B200 - 0x712E54B5E8 held the old startflags and is free
+SUBSDK_WARP_TO_START_OFFSET = 0x712E54B5E8
+# 0x712E54B5F4 - 0x712E54B6BC held the old start counts and is free
+SUBSDK_RANDOMIZER_SETTINGS_OFFSET = 0x712E54B6C0
+MAX_RANDOMIZER_SETTINGS_SIZE = 0x40
+SUBSDK_ER_FIXUP_RULES_OFFSET = 0x712E54B700
+MAX_ER_FIXUP_RULES = 32
+ER_FIXUP_RULE_SIZE = 60
+MAX_ER_FIXUP_FLAG_RANGES = 4
+SUBSDK_NIGHT_VALIDITY_RULES_OFFSET = (
+    SUBSDK_ER_FIXUP_RULES_OFFSET + MAX_ER_FIXUP_RULES * ER_FIXUP_RULE_SIZE
+)
+MAX_NIGHT_VALIDITY_RULES = 32
+MAX_NIGHT_VALIDITY_ENTRANCES = 7
+SUBSDK_ENTRANCE_REMAPS_OFFSET = (
+    SUBSDK_NIGHT_VALIDITY_RULES_OFFSET + MAX_NIGHT_VALIDITY_RULES * 16
+)
+MAX_ENTRANCE_REMAPS = 128
+SUBSDK_WARP_DESTINATIONS_OFFSET = (
+    SUBSDK_ENTRANCE_REMAPS_OFFSET + MAX_ENTRANCE_REMAPS * 24
+)
+MAX_WARP_DESTINATIONS = 48
+SUBSDK_STARTFLAGS_OFFSET = SUBSDK_WARP_DESTINATIONS_OFFSET + MAX_WARP_DESTINATIONS * 20
+MAX_STARTFLAGS_SIZE = 0x800
+STARTFLAGS_MAGIC = b"STFL"
+STARTFLAGS_VERSION = 4
+SUBSDK_ITEM_DISPLAY_INFO_OFFSET = SUBSDK_STARTFLAGS_OFFSET + MAX_STARTFLAGS_SIZE
+ITEM_DISPLAY_INFO_COUNT = 256
+# Matches FIRST_CUSTOM_ITEMID and LAST_CUSTOM_ITEMID in customitem.rs
+CUSTOM_ITEMIDS = range(214, 240)
+SUBSDK_PROGRESSIVE_ITEM_CHAINS_OFFSET = SUBSDK_ITEM_DISPLAY_INFO_OFFSET + 0x1800
+MAX_PROGRESSIVE_ITEM_CHAINS = 16
+MAX_PROGRESSIVE_ITEM_STAGES = 6
+SUBSDK_DUNGEON_ITEMS_OFFSET = SUBSDK_PROGRESSIVE_ITEM_CHAINS_OFFSET + 0xF40
+MAX_DUNGEON_ITEMS = 64
+
+# Writable blocks shared with external tools (see multiworld.rs)
+SUBSDK_MULTIWORLD_MAILBOX_OFFSET = 0x712E5FF100
+MULTIWORLD_MAILBOX_MAGIC = b"MWMB"
+MULTIWORLD_MAILBOX_VERSION = 2
+MULTIWORLD_MAILBOX_CAPACITY = 32
+MULTIWORLD_MAILBOX_STATUS_OK = 0
+MULTIWORLD_MAILBOX_STATUS_NO_SAVE_DATA = 1
+MULTIWORLD_MAILBOX_STATUS_INVALID_ITEM = 2
+
+# RandoSaveData in savefile.rs, relative to the start of a SaveFile
+SAVEFILE_SIZE = 0x53C0
+SAVEFILE_RANDO_DATA_OFFSET = 0x1464
+SAVEFILE_RANDO_DATA_SIZE = 0x600
+RANDO_SAVE_DATA_MAGIC = b"RSAV"
+RANDO_SAVE_DATA_VERSION = 1
+SAVEFILE_ENTRANCE_HISTORY_OFFSET = SAVEFILE_RANDO_DATA_OFFSET + 12
+
+# See tracker.rs
+SUBSDK_LOCATION_LOG_OFFSET = 0x712E5FF300
+LOCATION_LOG_MAGIC = b"LCLG"
+LOCATION_LOG_VERSION = 1
+LOCATION_LOG_CAPACITY = 64
+SUBSDK_EVENT_LOCATION_ID_OFFSET = 0x712E5FF040
+SUBSDK_RANDOMIZED_SCENEFLAGS_OFFSET = 0x712E551000
+MAX_RANDOMIZED_SCENEFLAGS = 256
+SUBSDK_TRACKER_STATE_OFFSET = 0x712E5FF700
+TRACKER_STATE_MAGIC = b"TRKS"
+TRACKER_STATE_VERSION = 1
+
+# See entrance.rs
+ENTRANCE_HISTORY_CAPACITY = 32
+SAVEFILE_LOCATION_FLAGS_OFFSET = SAVEFILE_ENTRANCE_HISTORY_OFFSET + 772
+LOCATION_FLAG_COUNT = 4096
+# Item actors and tgreacts only have 10 bits for their location flag
+ACTOR_LOCATION_FLAG_NONE = 0x3FF
 
 
 # Offsets defined as the equivalent subsdk1 offset plus its size.
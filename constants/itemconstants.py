@@ -522,6 +522,56 @@ ITEM_COUNTS = {
     HEART_PIECE: (0x5E, 1, 32),
 }
 
+# Each dungeon's Key Ring and the Small Key it stands in for. A Key Ring gives
+# the maximum count of its Small Key from ITEM_COUNTS
+KEY_RING_SMALL_KEYS = {
+    SV_KEY_RING: SV_SMALL_KEY,
+    LMF_KEY_RING: LMF_SMALL_KEY,
+    AC_KEY_RING: AC_SMALL_KEY,
+    FS_KEY_RING: FS_SMALL_KEY,
+    SSH_KEY_RING: SSH_SMALL_KEY,
+    SK_KEY_RING: SK_SMALL_KEY,
+}
+
+# Items that go into the Adventure Pouch
+# first value of tuple is the itemid
+# second is the extra data stored in the upper half of the slot (durability for
+# shields)
+ITEM_POUCH_DATA = {
+    WOODEN_SHIELD: (116, 8),
+    BANDED_SHIELD: (117, 12),
+    BRACED_SHIELD: (118, 16),
+    IRON_SHIELD: (119, 12),
+    REINFORCED_SHIELD: (120, 18),
+    FORTIFIED_SHIELD: (121, 24),
+    SACRED_SHIELD: (122, 20),
+    DIVINE_SHIELD: (123, 25),
+    GODDESS_SHIELD: (124, 30),
+    HYLIAN_SHIELD: (125, 0x30),
+    EMPTY_BOTTLE: (153, 0),
+    BOTTLE_OF_WATER: (54, 0),
+    GUARDIAN_POTION: (65, 0),
+    GUARDIAN_POTION_PLUS: (66, 0),
+    HEART_POTION: (78, 0),
+    HEART_POTION_PLUS: (79, 0),
+    HEART_POTION_PLUS_PLUS: (81, 0),
+    STAMINA_POTION: (84, 0),
+    STAMINA_POTION_PLUS: (85, 0),
+    AIR_POTION: (86, 0),
+    AIR_POTION_PLUS: (87, 0),
+    FAIRY_IN_A_BOTTLE: (88, 0),
+    REVITALIZING_POTION: (126, 0),
+    REVITALIZING_POTION_PLUS: (127, 0),
+    REVITALIZING_POTION_PLUS_PLUS: (194, 0),
+    BUG_MEDAL: (70, 0),
+    HEART_MEDAL: (100, 0),
+    RUPEE_MEDAL: (101, 0),
+    TREASURE_MEDAL: (102, 0),
+    POTION_MEDAL: (103, 0),
+    CURSED_MEDAL: (104, 0),
+    LIFE_MEDAL: (114, 0),
+}
+
 TRAP_SETTING_TO_ITEM = {
     "burn_traps": BURN_TRAP,
     "curse_traps": CURSE_TRAP,
@@ -15,6 +15,7 @@ SEED_SATCHEL = "Seed Satchel"
 QUIVER = "Quiver"
 FULL_ET_KEY = "Full ET Key"
 BOSS_KEY = "Boss Key"
+KEY_RING = "Key Ring"
 
 # 0
 CUPBOARD_TEXT = "Cupboard Text"
@@ -646,16 +647,22 @@ GROUP_OF_TADTONES = "Group of Tadtones"
 SCRAPPER = "Scrapper"
 
 # 216
+SV_KEY_RING = "Skyview Temple Key Ring"
 
 # 217
+LMF_KEY_RING = "Lanayru Mining Facility Key Ring"
 
 # 218
+AC_KEY_RING = "Ancient Cistern Key Ring"
 
 # 219
+FS_KEY_RING = "Fire Sanctuary Key Ring"
 
 # 220
+SSH_KEY_RING = "Sandship Key Ring"
 
 # 221
+SK_KEY_RING = "Sky Keep Key Ring"
 
 # 222
 
@@ -126,6 +126,20 @@ DEFAULT_GIVE_ITEM_FLOW = {
     "next": -1,
 }
 
+# Custom command, tells the game which location the give item command that
+# follows is for (see set_event_location in tracker.rs). Uses the same subType
+# as the custom commands in eventpatches.yaml
+DEFAULT_EVENT_LOCATION_FLOW = {
+    "type": "type3",
+    "subType": -1,
+    "param1": -1,  # event file number
+    "param2": -1,  # flow index of the give item command
+    "param3": 85,
+    "param4": 0,
+    "param5": 0,
+    "next": -1,
+}
+
 DEFAULT_SET_ITEM_FLAG_FLOW = {
     "type": "type3",
     "subType": 0,
@@ -192,6 +206,97 @@ DEFAULT_UNSET_ZONEFLAG_FLOW = {
     "next": -1,
 }
 
+# Custom event commands, see custom_event_commands in event.rs. These use the
+# same subType as the custom commands in eventpatches.yaml
+DEFAULT_UNSET_TEMPFLAG_FLOW = {
+    "type": "type3",
+    "subType": -1,
+    "param1": -1,  # tempflag id
+    "param2": 0,
+    "param3": 77,  # remove tempflag command
+    "param4": 0,
+    "param5": 0,
+    "next": -1,
+}
+
+# Follow these with a switch using param3 7 to branch on the result
+DEFAULT_CHECK_TEMPFLAG_FLOW = {
+    "type": "type3",
+    "subType": -1,
+    "param1": -1,  # tempflag id
+    "param2": 0,
+    "param3": 78,  # check tempflag command
+    "param4": 0,
+    "param5": 0,
+    "next": -1,
+}
+
+DEFAULT_CHECK_ZONEFLAG_FLOW = {
+    "type": "type3",
+    "subType": -1,
+    "param1": -1,  # zoneflag id
+    "param2": 0xFFFF,  # roomid, 0xFFFF for the current room
+    "param3": 79,  # check zoneflag command
+    "param4": 0,
+    "param5": 0,
+    "next": -1,
+}
+
+DEFAULT_SET_STAGE_OBJECT_FLAG_FLOW = {
+    "type": "type3",
+    "subType": -1,
+    "param1": -1,  # object id
+    "param2": 0xFFFF,  # sceneindex, 0xFFFF for the current scene
+    "param3": 80,  # set stage object flag command
+    "param4": 0,
+    "param5": 0,
+    "next": -1,
+}
+
+DEFAULT_UNSET_STAGE_OBJECT_FLAG_FLOW = {
+    "type": "type3",
+    "subType": -1,
+    "param1": -1,  # object id
+    "param2": 0xFFFF,  # sceneindex, 0xFFFF for the current scene
+    "param3": 81,  # remove stage object flag command
+    "param4": 0,
+    "param5": 0,
+    "next": -1,
+}
+
+DEFAULT_CHECK_STAGE_OBJECT_FLAG_FLOW = {
+    "type": "type3",
+    "subType": -1,
+    "param1": -1,  # object id
+    "param2": 0xFFFF,  # sceneindex, 0xFFFF for the current scene
+    "param3": 82,  # check stage object flag command
+    "param4": 0,
+    "param5": 0,
+    "next": -1,
+}
+
+DEFAULT_SET_LOCATION_FLAG_FLOW = {
+    "type": "type3",
+    "subType": -1,
+    "param1": -1,  # location flag id
+    "param2": 0,
+    "param3": 83,  # set location flag command
+    "param4": 0,
+    "param5": 0,
+    "next": -1,
+}
+
+DEFAULT_CHECK_LOCATION_FLAG_FLOW = {
+    "type": "type3",
+    "subType": -1,
+    "param1": -1,  # location flag id
+    "param2": 0,
+    "param3": 84,  # check location flag command
+    "param4": 0,
+    "param5": 0,
+    "next": -1,
+}
+
 DEFAULT_ATTENTION_MARK_FLOW = {
     "type": "type3",
     "subType": 0,
@@ -234,6 +339,14 @@ FLOW_ADD_VARIATIONS = (
     "settempflag",
     "setzoneflag",
     "unsetzoneflag",
+    "unsettempflag",
+    "checktempflag",
+    "checkzoneflag",
+    "setstageobjectflag",
+    "unsetstageobjectflag",
+    "checkstageobjectflag",
+    "setlocationflag",
+    "checklocationflag",
     "attentionmark",
 )
 SWITCH_ADD_VARIATIONS = ("switchadd", "checkstoryflag", "checksceneflag")
@@ -243,6 +356,14 @@ PARAM1_ALIASES = (
     "settempflag",
     "setzoneflag",
     "unsetzoneflag",
+    "unsettempflag",
+    "checktempflag",
+    "checkzoneflag",
+    "setstageobjectflag",
+    "unsetstageobjectflag",
+    "checkstageobjectflag",
+    "setlocationflag",
+    "checklocationflag",
 )
 PARAM2_ALIASES = (
     "itemid",
@@ -261,6 +382,14 @@ DEFAULT_FLOW_TYPE_LOOKUP = {
     "settempflag": DEFAULT_SET_TEMPFLAG_FLOW,
     "setzoneflag": DEFAULT_SET_ZONEFLAG_FLOW,
     "unsetzoneflag": DEFAULT_UNSET_ZONEFLAG_FLOW,
+    "unsettempflag": DEFAULT_UNSET_TEMPFLAG_FLOW,
+    "checktempflag": DEFAULT_CHECK_TEMPFLAG_FLOW,
+    "checkzoneflag": DEFAULT_CHECK_ZONEFLAG_FLOW,
+    "setstageobjectflag": DEFAULT_SET_STAGE_OBJECT_FLAG_FLOW,
+    "unsetstageobjectflag": DEFAULT_UNSET_STAGE_OBJECT_FLAG_FLOW,
+    "checkstageobjectflag": DEFAULT_CHECK_STAGE_OBJECT_FLAG_FLOW,
+    "setlocationflag": DEFAULT_SET_LOCATION_FLAG_FLOW,
+    "checklocationflag": DEFAULT_CHECK_LOCATION_FLAG_FLOW,
     "attentionmark": DEFAULT_ATTENTION_MARK_FLOW,
     "switchadd": DEFAULT_FLOW,
     "checkstoryflag": DEFAULT_CHECK_STORYFLAG_FLOW,
@@ -282,6 +411,35 @@ EVENT_PATCH_PATH_REGEX = re.compile(r"event/(?P<eventFile>[^/]+)/(?P<eventID>[^/
 OARC_ADD_PATH_REGEX = re.compile(r"oarc/(?P<stage>[^/]+)/l(?P<layer>[^/]+)")
 SHOP_PATCH_PATH_REGEX = re.compile(r"ShpSmpl/(?P<index>[0-9]+)")
 
+# Mapping of each closet (stage, roomid, objectid) to the local scene flag we'll use
+CLOSET_SCENEFLAGS: dict[tuple[str, int, int], int] = {
+    ("F001r", 1, 0xFC08): 12,  # Link's Closet            0x10
+    ("F001r", 1, 0xFC07): 24,  # Fledge's Closet          2x01
+    ("F001r", 6, 0xFC07): 32,  # Zelda's Closet           5x01 (vanilla scene flag)
+    ("F001r", 2, 0xFC03): 48,  # Groose's Closet          7x01
+    ("F001r", 5, 0xFC05): 50,  # Owlan's Closet           7x04
+    ("F001r", 4, 0xFC03): 57,  # Horwell's Closet         6x02
+    ("F001r", 6, 0xFC06): 61,  # Karane's Closet          6x20
+    ("F005r", 0, 0xFC0E): 81,  # O&P's Closet             Bx02
+    ("F006r", 0, 0xFC16): 82,  # Wryna's Closet           Bx04
+    ("F013r", 0, 0xFC0E): 100,  # Sparrot's Closet        Dx10
+    ("F014r", 0, 0xFC12): 101,  # Luv and Bertie's Closet Dx20
+    ("F015r", 0, 0xFC10): 105,  # Gondo's Closet          Cx02
+    ("F016r", 0, 0xFC28): 107,  # Mallara's Closet        Cx04
+    ("F017r", 0, 0xFC1E): 116,  # Rupin's Closet          Fx10
+    ("F018r", 0, 0xFC11): 117,  # Peater's Closet         Fx20
+    ("F018r", 0, 0xFC12): 118,  # Peatrice's Closet       Fx40
+    ("F011r", 0, 0xFC3B): 3,  # Pumm and Kina's Closet    1x08
+    ("F301_5", 0, 0xFC0C): 0,  # Skipper's Closet         2x01
+}
+
+# Sceneflag given to the item of each squirrel (MssbTag) by its object id
+SQUIRREL_SCENEFLAGS: dict[int, int] = {
+    0xFCC8: 88,  # 0xA 01
+    0xFC9C: 89,  # 0xA 02
+    0xFCA0: 90,  # 0xA 04
+}
+
 LANGUAGE_NAME_TO_FILE_ID = {
     "chinese": "zh_CN",
     "dutch": "nl_NL",
@@ -100,6 +100,12 @@ OBJECTPACK_PATH = SSHD_EXTRACT_PATH / OBJECTPACK_PATH_TAIL
 TEXT_DATA_PATH = RANDO_ROOT_PATH / "data" / "text_data"
 ENTRANCE_SHUFFLE_DATA_PATH = RANDO_ROOT_PATH / "data" / "entrance_shuffle_data.yaml"
 BIRD_STATUE_DATA_PATH = RANDO_ROOT_PATH / "data" / "bird_statue_data.yaml"
+NIGHT_VALIDITY_DATA_PATH = RANDO_ROOT_PATH / "data" / "night_validity.yaml"
+ITEM_DISPLAY_INFO_DATA_PATH = RANDO_ROOT_PATH / "data" / "item_display_info.yaml"
+PROGRESSIVE_ITEMS_DATA_PATH = RANDO_ROOT_PATH / "data" / "progressive_items.yaml"
+DUNGEON_ITEMS_DATA_PATH = RANDO_ROOT_PATH / "data" / "dungeon_items.yaml"
+WARP_DESTINATIONS_DATA_PATH = RANDO_ROOT_PATH / "data" / "warp_destinations.yaml"
+RUNTIME_SETTINGS_DATA_PATH = RANDO_ROOT_PATH / "data" / "runtime_settings.yaml"
 WORLD_DATA_PATH = RANDO_ROOT_PATH / "data" / "world"
 MACROS_DATA_PATH = RANDO_ROOT_PATH / "data" / "macros.yaml"
 
@@ -124,3 +130,4 @@ SUBSDK1_FILE_PATH = EXEFS_EXTRACT_PATH / "subsdk1"
 SDK_FILE_PATH = EXEFS_EXTRACT_PATH / "sdk"
 
 STARTFLAGS_FILE_PATH = RANDO_ROOT_PATH / "data" / "patches" / "startflags.yaml"
+ER_FIXUPS_FILE_PATH = RANDO_ROOT_PATH / "data" / "patches" / "erfixups.yaml"
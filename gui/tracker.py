@@ -1568,9 +1568,11 @@ class Tracker:
 
         item_pool = get_complete_item_pool([self.world])
         # Filter out keys from the item pool
-        # Small Keys must be first
+        # Small Keys (and Key Rings, which replace them) must be first
         own_dungeon_keys = [
-            item for item in item_pool if (item.is_dungeon_small_key and small_keys)
+            item
+            for item in item_pool
+            if (item.is_dungeon_small_key or item.is_key_ring) and small_keys
         ]
         own_dungeon_keys += [
             item for item in item_pool if (item.is_boss_key and boss_keys)
@@ -1586,7 +1588,7 @@ class Tracker:
         for key in own_dungeon_keys:
             current_dungeon = None
             for dungeon in self.world.dungeons.values():
-                if key in [dungeon.small_key, dungeon.boss_key]:
+                if key in dungeon.small_key_items() + [dungeon.boss_key]:
                     current_dungeon = dungeon
                     break
 
@@ -442,6 +442,11 @@ class Ui_main_window(object):
 
         self.verticalLayout_16.addWidget(self.setting_small_keys)
 
+        self.setting_small_key_rings = RandoTriStateCheckBox(self.dungeons_group_box)
+        self.setting_small_key_rings.setObjectName(u"setting_small_key_rings")
+
+        self.verticalLayout_16.addWidget(self.setting_small_key_rings)
+
         self.boss_keys_label = QLabel(self.dungeons_group_box)
         self.boss_keys_label.setObjectName(u"boss_keys_label")
 
@@ -2843,6 +2848,7 @@ class Ui_main_window(object):
         self.tab_widget.setTabText(self.tab_widget.indexOf(self.getting_started_tab), QCoreApplication.translate("main_window", u"Getting Started", None))
         self.dungeons_group_box.setTitle(QCoreApplication.translate("main_window", u"Dungeon Items", None))
         self.small_keys_label.setText(QCoreApplication.translate("main_window", u"Small Keys", None))
+        self.setting_small_key_rings.setText(QCoreApplication.translate("main_window", u"Small Key Rings", None))
         self.boss_keys_label.setText(QCoreApplication.translate("main_window", u"Boss Keys", None))
         self.map_mode_label.setText(QCoreApplication.translate("main_window", u"Dungeon Maps", None))
         self.lanayru_caves_keys_label.setText(QCoreApplication.translate("main_window", u"Lanayru Caves Small Key", None))
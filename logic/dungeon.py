@@ -20,12 +20,17 @@ class Dungeon:
         self.unrequired_reasons = ""
         self.world: "World" = None
         self.small_key: Item = None
+        self.key_ring: Item = None
         self.boss_key: Item = None
         self.map: Item = None
 
     def __str__(self) -> str:
         return self.name
 
+    # The small key and the key ring get placed by the same setting
+    def small_key_items(self) -> list[Item]:
+        return [item for item in (self.small_key, self.key_ring) if item is not None]
+
     def should_be_barren(self) -> bool:
         return (
             not self.required
@@ -267,8 +267,7 @@ def place_own_dungeon_items(world: World, worlds: list[World]):
         own_dungeon_items: list[Item] = []
 
         if world.setting("small_keys") == "own_dungeon":
-            small_key = dungeon.small_key
-            if small_key is not None:
+            for small_key in dungeon.small_key_items():
                 own_dungeon_items.extend([small_key] * world.item_pool[small_key])
                 world.item_pool[small_key] = 0
 
@@ -320,8 +319,7 @@ def place_own_region_items(world: World, worlds: list[World]):
         own_region_locations: list[Location] = []
 
         if world.setting("small_keys") == "own_region":
-            small_key = dungeon.small_key
-            if small_key is not None:
+            for small_key in dungeon.small_key_items():
                 own_region_items.extend([small_key] * world.item_pool[small_key])
                 world.item_pool[small_key] = 0
 
@@ -380,8 +378,7 @@ def place_any_dungeon_items(world: World, worlds: list[World]):
         any_dungeon_locations.clear()
         for dungeon in dungeons:
             if world.setting("small_keys") == "any_dungeon":
-                small_key = dungeon.small_key
-                if small_key is not None:
+                for small_key in dungeon.small_key_items():
                     any_dungeon_items.extend([small_key] * world.item_pool[small_key])
                     world.item_pool[small_key] = 0
 
@@ -421,8 +418,7 @@ def place_overworld_items(world: World, worlds: list[World]):
 
     for dungeon in world.dungeons.values():
         if world.setting("small_keys") == "overworld":
-            small_key = dungeon.small_key
-            if small_key is not None:
+            for small_key in dungeon.small_key_items():
                 overworld_items.extend([small_key] * world.item_pool[small_key])
                 world.item_pool[small_key] = 0
 
@@ -167,7 +167,10 @@ def calculate_possible_path_locations(worlds: list[World]) -> None:
                     location.has_known_vanilla_item
                     or location.is_goal_location
                     or (
-                        item_at_location.is_dungeon_small_key
+                        (
+                            item_at_location.is_dungeon_small_key
+                            or item_at_location.is_key_ring
+                        )
                         and world.setting("small_keys").is_any_of(
                             "own_dungeon", "own_region"
                         )
@@ -229,7 +232,10 @@ def calculate_possible_barren_regions(worlds: list[World]) -> None:
             if (
                 location.is_goal_location
                 or (
-                    item_at_location.is_dungeon_small_key
+                    (
+                        item_at_location.is_dungeon_small_key
+                        or item_at_location.is_key_ring
+                    )
                     and world.setting("small_keys").is_any_of("vanilla", "own_dungeon")
                 )
                 or (
@@ -407,7 +413,10 @@ def generate_item_hint_locations(world: World, hint_locations: list) -> None:
             and not location.has_known_vanilla_item
             and not location.is_hinted
             and not (
-                location.current_item.is_dungeon_small_key
+                (
+                    location.current_item.is_dungeon_small_key
+                    or location.current_item.is_key_ring
+                )
                 and world.setting("small_keys").is_any_of("own_dungeon", "own_region")
             )
             and not (
@@ -28,6 +28,12 @@ class Item:
         )
         self.is_boss_key: bool = " Boss Key" in name_
         self.is_dungeon_map: bool = " Map" in name_
+        self.is_key_ring: bool = " Key Ring" in name_
+
+        # Set on a dungeon's small key when the dungeon has a key ring. Owning
+        # the key ring counts as owning key_ring_size of the small key
+        self.key_ring: "Item" = None
+        self.key_ring_size: int = 0
 
     def __str__(self) -> str:
         return (
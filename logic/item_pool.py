@@ -41,6 +41,19 @@ def generate_item_pool(world: "World") -> None:
             if not item.endswith(SMALL_KEY) or item == LC_SMALL_KEY
         ]
 
+    # Swap each dungeon's Small Keys for its Key Ring
+    if world.setting("small_key_rings") == "on" and not world.setting(
+        "small_keys"
+    ).is_any_of("vanilla", "removed"):
+        key_rings = [
+            key_ring
+            for key_ring, small_key in KEY_RING_SMALL_KEYS.items()
+            if small_key in item_pool
+        ]
+        item_pool = [
+            item for item in item_pool if item not in KEY_RING_SMALL_KEYS.values()
+        ] + key_rings
+
     if world.setting("lanayru_caves_keys") == "removed":
         item_pool = [item for item in item_pool if item != LC_SMALL_KEY]
 
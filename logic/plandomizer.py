@@ -60,6 +60,16 @@ def load_plandomizer_data(worlds: list[World], filepath: Path):
 
                     world.plandomizer_entrances[entrance] = target
 
+            if "entrance_remaps" in world_data:
+                for remap in world_data["entrance_remaps"]:
+                    for field in ["from", "to"]:
+                        if field not in remap:
+                            raise PlandomizerError(
+                                f"An entrance remap in {world} is missing the {field} field"
+                            )
+
+                    world.plandomizer_entrance_remaps.append(remap)
+
             if "sometimes_hint_locations" in world_data:
                 # Clear previous sometimes locations
                 for location in world.get_all_item_locations():
@@ -312,6 +312,14 @@ def parse_requirement_string(
     return req
 
 
+# A dungeon's key ring counts as all of its small keys
+def get_owned_item_count(search: "Search", item) -> int:
+    count = search.owned_items[item]
+    if item.key_ring is not None:
+        count += search.owned_items[item.key_ring] * item.key_ring_size
+    return count
+
+
 def evaluate_requirement_at_time(
     req: Requirement, search: "Search", time: int, world: "World"
 ) -> bool:
@@ -343,12 +351,12 @@ def evaluate_requirement_at_time(
 
         case RequirementType.ITEM:
             item = req.args[0]
-            return search.owned_items[item] > 0
+            return get_owned_item_count(search, item) > 0
 
         case RequirementType.COUNT:
             count = req.args[0]
             item = req.args[1]
-            return search.owned_items[item] >= count
+            return get_owned_item_count(search, item) >= count
 
         case RequirementType.EVENT:
             id = req.args[0]
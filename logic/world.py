@@ -1,5 +1,16 @@
-from constants.itemconstants import ALL_JUNK_ITEMS, TRAP_SETTING_TO_ITEM, BOTTLE_ITEMS
-from filepathconstants import ITEMS_PATH, MACROS_DATA_PATH, WORLD_DATA_PATH
+from constants.itemconstants import (
+    ALL_JUNK_ITEMS,
+    TRAP_SETTING_TO_ITEM,
+    BOTTLE_ITEMS,
+    ITEM_COUNTS,
+)
+from filepathconstants import (
+    ENTRANCE_SHUFFLE_DATA_PATH,
+    ITEMS_PATH,
+    MACROS_DATA_PATH,
+    NIGHT_VALIDITY_DATA_PATH,
+    WORLD_DATA_PATH,
+)
 from logic.location_table import build_location_table, get_disabled_shuffle_locations
 from .config import Config
 from .settings import *
@@ -10,6 +21,7 @@ from .requirements import *
 from .item_pool import *
 from .dungeon import *
 from .search import game_beatable, Search, SearchMode
+from util.night_validity import get_day_only_areas
 from util.text import *
 
 from collections import Counter, OrderedDict
@@ -67,6 +79,8 @@ class World:
 
         self.plandomizer_locations: dict[Location, Item] = {}
         self.plandomizer_entrances: dict[Entrance, Entrance] = {}
+        # Raw runtime entrance remaps, see util/entrance_remaps.py
+        self.plandomizer_entrance_remaps: list[dict] = []
 
         # Hint related things
         # path_locations maps a goal location to its set of path locations
@@ -144,6 +158,19 @@ class World:
                     logging.getLogger("").debug(
                         f"Assigned {item} as boss key for dungeon {dungeon_name}"
                     )
+                elif item.is_key_ring:
+                    dungeon_name = item.name.replace(" Key Ring", "")
+                    self.add_dungeon(dungeon_name)
+                    dungeon = self.get_dungeon(dungeon_name)
+                    dungeon.key_ring = item
+                    # Small keys come before key rings in items.yaml
+                    dungeon.small_key.key_ring = item
+                    dungeon.small_key.key_ring_size = ITEM_COUNTS[
+                        dungeon.small_key.name
+                    ][2]
+                    logging.getLogger("").debug(
+                        f"Assigned {item} as key ring for dungeon {dungeon_name}"
+                    )
                 elif item.is_dungeon_map:
                     dungeon_name = item.name.replace(" Map", "")
                     self.add_dungeon(dungeon_name)
@@ -195,16 +222,6 @@ class World:
                     new_area.world = self
                     defined_areas.add(new_area)
 
-                    if (
-                        "allowed_time_of_day" in area_node
-                        and self.setting("natural_night_connections") == "on"
-                    ):
-                        new_area.allowed_tod = (
-                            TOD.DAY
-                            if area_node["allowed_time_of_day"] == "Day Only"
-                            else TOD.ALL
-                        )
-
                     new_area.can_sleep = area_node.get("can_sleep", False)
 
                     if dungeon_name := area_node.get("dungeon", False):
@@ -280,6 +297,20 @@ class World:
             for exit_ in area.exits:
                 exit_.connected_area.entrances.append(exit_)
 
+        if self.setting("natural_night_connections") == "on":
+            self.load_day_only_areas()
+
+    # data/night_validity.yaml is also used by the game, so both agree on which
+    # areas can't be entered at night
+    def load_day_only_areas(self) -> None:
+        with open(NIGHT_VALIDITY_DATA_PATH, "r", encoding="utf-8") as rules_file:
+            rules = yaml.safe_load(rules_file)
+        with open(ENTRANCE_SHUFFLE_DATA_PATH, "r", encoding="utf-8") as entrance_file:
+            entrance_data = yaml.safe_load(entrance_file)
+
+        for area_name in get_day_only_areas(rules, entrance_data):
+            self.get_area(area_name).allowed_tod = TOD.DAY
+
     def verify_hint_data(self) -> None:
         # Verify that every item, location, and hint region has text data
         for item in self.item_table.values():
@@ -1,10 +1,11 @@
-import struct
 import tempfile
 from constants.itemconstants import (
     ITEM_ITEMFLAGS,
     ITEM_STORYFLAGS,
     ITEM_DUNGEONFLAGS,
     ITEM_COUNTS,
+    ITEM_POUCH_DATA,
+    KEY_RING_SMALL_KEYS,
     PROGRESSIVE_POUCH,
 )
 from filepathconstants import (
@@ -16,6 +17,13 @@ from filepathconstants import (
     STARTFLAGS_FILE_PATH,
     SUBSDK1_FILE_PATH,
     BIRD_STATUE_DATA_PATH,
+    ER_FIXUPS_FILE_PATH,
+    DUNGEON_ITEMS_DATA_PATH,
+    ITEM_DISPLAY_INFO_DATA_PATH,
+    NIGHT_VALIDITY_DATA_PATH,
+    PROGRESSIVE_ITEMS_DATA_PATH,
+    RUNTIME_SETTINGS_DATA_PATH,
+    WARP_DESTINATIONS_DATA_PATH,
 )
 from io import BytesIO
 from pathlib import Path
@@ -29,11 +37,41 @@ from gui.dialogs.dialog_header import print_progress_text
 from logic.world import World
 
 from patches.asmpatchhelper import NsoOffsets, SegmentHeader
+from patches.checkpatchhandler import get_randomized_sceneflags
 from patches.conditionalpatchhandler import ConditionalPatchHandler
 
 from sslib.fs_helpers import write_bytes, write_str, write_u32, write_u8
 from sslib.utils import write_bytes_create_dirs
 from sslib.yaml import yaml_load, yaml_write
+from util.dungeon_items import pack_dungeon_items
+from util.entrance_remaps import pack_entrance_remaps
+from util.er_fixups import pack_er_fixup_rules
+from util.item_display import pack_item_display_info
+from util.multiworld import empty_mailbox
+from util.night_validity import pack_night_validity_rules
+from util.pouch_loadout import get_loadout
+from util.progressive_items import pack_progressive_item_chains
+from util.runtime_settings import RuntimeSettingsError, pack_randomizer_settings
+from util.startflags import (
+    STARTFLAG_TAG_COUNTER,
+    STARTFLAG_TAG_DUNGEONFLAG,
+    STARTFLAG_TAG_ITEMFLAG,
+    STARTFLAG_TAG_LOADOUT_ITEM,
+    STARTFLAG_TAG_SCENEFLAG,
+    STARTFLAG_TAG_STAGE_OBJECT_FLAG,
+    STARTFLAG_TAG_STORYFLAG,
+    STARTFLAG_TAG_TEMPFLAG,
+    STARTFLAG_TAG_ZONEFLAG,
+    StartflagRecord,
+    get_stage_object_flags,
+    pack_startflags,
+)
+from util.tracker import (
+    empty_location_log,
+    empty_tracker_state,
+    pack_randomized_sceneflags,
+)
+from util.warp_destinations import get_warp_destinations, pack_warp_destinations
 
 
 # Adds a patch to nnSdk to route all vfprintf calls to the debug output
@@ -276,6 +314,40 @@ class ASMPatchHandler:
             startflags_diff_file_path = temp_dir_name / "startflags-diff.yaml"
             self.patch_startflags(startflags_diff_file_path, world, onlyif_handler)
 
+            print("Writing ER fixup rules")
+            er_fixups_diff_file_path = temp_dir_name / "er-fixups-diff.yaml"
+            self.patch_er_fixups(er_fixups_diff_file_path)
+
+            print("Writing night validity rules")
+            night_validity_diff_file_path = temp_dir_name / "night-validity-diff.yaml"
+            self.patch_night_validity(night_validity_diff_file_path)
+
+            print("Writing entrance remaps")
+            entrance_remaps_diff_file_path = temp_dir_name / "entrance-remaps-diff.yaml"
+            self.patch_entrance_remaps(entrance_remaps_diff_file_path, world)
+
+            print("Writing warp destinations")
+            warp_destinations_diff_file_path = (
+                temp_dir_name / "warp-destinations-diff.yaml"
+            )
+            self.patch_warp_destinations(warp_destinations_diff_file_path, world)
+
+            print("Writing item display info")
+            item_display_info_diff_file_path = (
+                temp_dir_name / "item-display-info-diff.yaml"
+            )
+            self.patch_item_display_info(item_display_info_diff_file_path)
+
+            print("Writing progressive item chains")
+            progressive_items_diff_file_path = (
+                temp_dir_name / "progressive-items-diff.yaml"
+            )
+            self.patch_progressive_items(progressive_items_diff_file_path)
+
+            print("Writing dungeon items")
+            dungeon_items_diff_file_path = temp_dir_name / "dungeon-items-diff.yaml"
+            self.patch_dungeon_items(dungeon_items_diff_file_path)
+
             print("Initializing global variables")
             global_variables_diff_file_path = (
                 temp_dir_name / "global-variables-diff.yaml"
@@ -290,6 +362,14 @@ class ASMPatchHandler:
             print_progress_text("Patching Starting Entrance")
             self.patch_starting_entrance(staring_entrance_diff_file_path, world)
 
+            print("Writing randomized sceneflags")
+            randomized_sceneflags_diff_file_path = (
+                temp_dir_name / "randomized-sceneflags-diff.yaml"
+            )
+            self.patch_randomized_sceneflags(
+                randomized_sceneflags_diff_file_path, world
+            )
+
             print_progress_text("Applying asm additions")
             self.patch_asm(
                 world,
@@ -301,15 +381,19 @@ class ASMPatchHandler:
                 extra_diffs_path=temp_dir_name,
             )
 
+    def patch_randomized_sceneflags(self, output_path: Path, world: World):
+        randomized_sceneflags_bytes = pack_randomized_sceneflags(
+            get_randomized_sceneflags(world)
+        )
+
+        self._write_table(
+            output_path,
+            SUBSDK_RANDOMIZED_SCENEFLAGS_OFFSET,
+            randomized_sceneflags_bytes,
+        )
+
     def patch_starting_entrance(self, output_path: Path, world: World):
-        try:
-            spawn_info = world.get_entrance(
-                "Link's Spawn -> Knight Academy"
-            ).replaces.spawn_info[0]
-        except:
-            spawn_info = world.get_entrance(
-                "Link's Spawn -> Knight Academy"
-            ).spawn_info[0]
+        spawn_info = self._get_start_spawn_info(world)
 
         # print(spawn_info)
 
@@ -332,16 +416,7 @@ class ASMPatchHandler:
         spawn_data_bytes = spawn_data.getvalue()
         assert len(spawn_data_bytes) == 12
 
-        spawn_data_dict = {
-            SUBSDK_WARP_TO_START_OFFSET: list(
-                struct.unpack("B" * len(spawn_data_bytes), spawn_data_bytes)
-            )
-        }
-
-        yaml_write(output_path, spawn_data_dict)
-
-        # Write the startflag binary to a non-temp file.
-        # yaml_write(Path("./test-starting-entrance.yaml"), spawn_data_dict)
+        self._write_table(output_path, SUBSDK_WARP_TO_START_OFFSET, spawn_data_bytes)
 
     def patch_startflags(
         self, output_path: Path, world: World, onlyif_handler: ConditionalPatchHandler
@@ -352,11 +427,25 @@ class ASMPatchHandler:
         sceneflags = startflags["Sceneflags"]
         itemflags = startflags["Itemflags"]
         dungeonflags = startflags["Dungeonflags"]
+        tempflags = startflags.get("Tempflags") or []
+        zoneflags = startflags.get("Zoneflags") or {}
+        stage_object_flags = startflags.get("StageObjectFlags") or {}
         start_counts = Counter()
+        pouch_items = []
+        pouch_count = 0
 
         for item, count in world.starting_item_pool.items():
             item_name = item.name
 
+            if item_name == PROGRESSIVE_POUCH:
+                pouch_count = count
+
+            if pouch_data := ITEM_POUCH_DATA.get(item_name, False):
+                itemid, extra = pouch_data
+                pouch_items.extend([(itemid, extra)] * count)
+                # Skips the first time textbox for the item
+                itemflags.append(itemid)
+
             if itemflag_data := ITEM_ITEMFLAGS.get(item_name, False):
                 if type(itemflag_data) == list:
                     for item_count in range(0, count):
@@ -390,6 +479,12 @@ class ASMPatchHandler:
                     final_count -= 1
                 start_counts[counter] += amount * final_count
 
+            # Key Rings don't have a counter of their own, they fill their
+            # dungeon's small key counter
+            if small_key := KEY_RING_SMALL_KEYS.get(item_name, False):
+                counter, amount, maximum = ITEM_COUNTS[small_key]
+                start_counts[counter] += amount * maximum
+
         # Set flags for random starting statues
         bird_statue_data = yaml_load(BIRD_STATUE_DATA_PATH)
         faron_starting_statue = world.get_entrance(
@@ -415,104 +510,140 @@ class ASMPatchHandler:
                     sceneflags[scene] = []
                 sceneflags[scene].append(flag)
 
-        # Each section is delimited by 0xFFFF
-        startflags_data = BytesIO()
-
-        # Storyflags
-        for flag in self._get_flags(storyflags, onlyif_handler):
-            startflags_data.write(struct.pack("<H", flag))
-
-        startflags_data.write(bytes.fromhex("FFFF"))
-
-        # Sceneflags
-        for scene in sceneflags:
-            for flag in self._get_flags(sceneflags[scene], onlyif_handler):
-                startflags_data.write(
-                    struct.pack("<BB", SCENE_NAME_TO_SCENE_INDEX[scene], flag)
-                )
-
-        startflags_data.write(bytes.fromhex("FFFF"))
-
         # Itemflags
-        itemflags.sort()  # Forces Hylian Shield to always be the top slot of the pouch wheel
-
-        for flag in self._get_flags(itemflags, onlyif_handler):
-            startflags_data.write(struct.pack("<H", flag))
+        itemflags = sorted(set(self._get_flags(itemflags, onlyif_handler)))
+
+        startflag_records = [
+            StartflagRecord(
+                STARTFLAG_TAG_STORYFLAG,
+                [(flag,) for flag in self._get_flags(storyflags, onlyif_handler)],
+            ),
+            StartflagRecord(
+                STARTFLAG_TAG_SCENEFLAG,
+                [
+                    (SCENE_NAME_TO_SCENE_INDEX[scene], flag)
+                    for scene in sceneflags
+                    for flag in self._get_flags(sceneflags[scene], onlyif_handler)
+                ],
+            ),
+            StartflagRecord(STARTFLAG_TAG_ITEMFLAG, [(flag,) for flag in itemflags]),
+            StartflagRecord(
+                STARTFLAG_TAG_DUNGEONFLAG,
+                [
+                    (SCENE_NAME_TO_SCENE_INDEX[scene], flag)
+                    for scene in dungeonflags
+                    for flag in self._get_flags(dungeonflags[scene], onlyif_handler)
+                ],
+            ),
+            StartflagRecord(
+                STARTFLAG_TAG_TEMPFLAG,
+                [(flag,) for flag in self._get_flags(tempflags, onlyif_handler)],
+            ),
+            StartflagRecord(
+                STARTFLAG_TAG_ZONEFLAG,
+                [
+                    (roomid, flag)
+                    for roomid in zoneflags
+                    for flag in self._get_flags(zoneflags[roomid], onlyif_handler)
+                ],
+            ),
+            StartflagRecord(STARTFLAG_TAG_COUNTER, list(start_counts.items())),
+            StartflagRecord(
+                STARTFLAG_TAG_LOADOUT_ITEM, get_loadout(pouch_items, pouch_count)
+            ),
+            StartflagRecord(
+                STARTFLAG_TAG_STAGE_OBJECT_FLAG,
+                get_stage_object_flags(
+                    {
+                        stage: self._get_flags(objectids, onlyif_handler)
+                        for stage, objectids in stage_object_flags.items()
+                    },
+                    self._get_start_spawn_info(world)["stage"],
+                ),
+            ),
+        ]
+
+        startflags_data_bytes = pack_startflags(startflag_records)
+        self._write_table(output_path, SUBSDK_STARTFLAGS_OFFSET, startflags_data_bytes)
+
+    def patch_er_fixups(self, output_path: Path):
+        er_fixups_data_bytes = pack_er_fixup_rules(yaml_load(ER_FIXUPS_FILE_PATH))
+
+        self._write_table(
+            output_path, SUBSDK_ER_FIXUP_RULES_OFFSET, er_fixups_data_bytes
+        )
 
-        startflags_data.write(bytes.fromhex("FFFF"))
+    def patch_night_validity(self, output_path: Path):
+        night_validity_data_bytes = pack_night_validity_rules(
+            yaml_load(NIGHT_VALIDITY_DATA_PATH)
+        )
 
-        # Dungeonflags
-        for scene in dungeonflags:
-            for flag in self._get_flags(dungeonflags[scene], onlyif_handler):
-                startflags_data.write(
-                    struct.pack("<BB", SCENE_NAME_TO_SCENE_INDEX[scene], flag)
-                )
+        self._write_table(
+            output_path, SUBSDK_NIGHT_VALIDITY_RULES_OFFSET, night_validity_data_bytes
+        )
 
-        startflags_data.write(bytes.fromhex("FFFF"))
+    def patch_item_display_info(self, output_path: Path):
+        item_display_info_data_bytes = pack_item_display_info(
+            yaml_load(ITEM_DISPLAY_INFO_DATA_PATH)
+        )
 
-        start_counts_data = BytesIO()
+        self._write_table(
+            output_path, SUBSDK_ITEM_DISPLAY_INFO_OFFSET, item_display_info_data_bytes
+        )
 
-        # Start counts
-        for counter, amount in start_counts.items():
-            start_counts_data.write(struct.pack("<HH", counter, amount))
+    def patch_progressive_items(self, output_path: Path):
+        progressive_items_data_bytes = pack_progressive_item_chains(
+            yaml_load(PROGRESSIVE_ITEMS_DATA_PATH)
+        )
 
-        start_counts_data.write(bytes.fromhex("FFFFFFFF"))
+        self._write_table(
+            output_path,
+            SUBSDK_PROGRESSIVE_ITEM_CHAINS_OFFSET,
+            progressive_items_data_bytes,
+        )
 
-        # Convert startflags_data into a list of bytes.
-        startflags_data_bytes = startflags_data.getvalue()
-        startflags_data_dict = {
-            SUBSDK_STARTFLAG_OFFSET: list(
-                struct.unpack("B" * len(startflags_data_bytes), startflags_data_bytes)
-            )
-        }
+    def patch_dungeon_items(self, output_path: Path):
+        dungeon_items_data_bytes = pack_dungeon_items(
+            yaml_load(DUNGEON_ITEMS_DATA_PATH)
+        )
 
-        # Same with start_counts_data
-        start_counts_data_bytes = start_counts_data.getvalue()
-        start_counts_data_dict = {
-            SUBSDK_START_COUNTS_OFFSET: list(
-                struct.unpack(
-                    "B" * len(start_counts_data_bytes), start_counts_data_bytes
-                )
-            )
-        }
+        self._write_table(
+            output_path, SUBSDK_DUNGEON_ITEMS_OFFSET, dungeon_items_data_bytes
+        )
 
-        startflags_data_dict.update(start_counts_data_dict)
+    def patch_entrance_remaps(self, output_path: Path, world: World):
+        entrance_remaps_data_bytes = pack_entrance_remaps(
+            world.plandomizer_entrance_remaps
+        )
 
-        yaml_write(output_path, startflags_data_dict)
+        self._write_table(
+            output_path, SUBSDK_ENTRANCE_REMAPS_OFFSET, entrance_remaps_data_bytes
+        )
 
-        # Write the startflag binary to a non-temp file.
-        # yaml_write(Path("./test-startflags.yaml"), startflags_data_dict)
+    def patch_warp_destinations(self, output_path: Path, world: World):
+        warp_destinations = get_warp_destinations(
+            yaml_load(WARP_DESTINATIONS_DATA_PATH),
+            yaml_load(BIRD_STATUE_DATA_PATH),
+            lambda entrance: world.get_entrance(entrance).spawn_info[0],
+        )
+        warp_destinations_data_bytes = pack_warp_destinations(warp_destinations)
 
-        # If this fails, the rust struct size will need increasing
-        assert len(startflags_data_bytes) < MAX_STARTFLAGS
+        self._write_table(
+            output_path, SUBSDK_WARP_DESTINATIONS_OFFSET, warp_destinations_data_bytes
+        )
 
     def init_global_variables(self, output_path: Path, world: World):
-
-        daytime_sky_color_index = world.setting_map.settings[
-            "daytime_sky_color"
-        ].current_option_index
-        nighttime_sky_color_index = world.setting_map.settings[
-            "nighttime_sky_color"
-        ].current_option_index
-        daytime_cloud_color_index = world.setting_map.settings[
-            "daytime_cloud_color"
-        ].current_option_index
-        nighttime_cloud_color_index = world.setting_map.settings[
-            "nighttime_cloud_color"
-        ].current_option_index
-        skip_harp_playing = world.setting("skip_harp_playing").value_index()
+        # RandomizerSettings in settings.rs, see data/runtime_settings.yaml
+        randomizer_settings = pack_randomizer_settings(
+            yaml_load(RUNTIME_SETTINGS_DATA_PATH), world.setting
+        )
+        if len(randomizer_settings) > MAX_RANDOMIZER_SETTINGS_SIZE:
+            raise RuntimeSettingsError(
+                f"RandomizerSettings is too big ({len(randomizer_settings)} bytes), the maximum is {MAX_RANDOMIZER_SETTINGS_SIZE}"
+            )
 
         init_rw_globals_dict = {
-            0x712E54B6BC: [
-                daytime_sky_color_index,
-                nighttime_sky_color_index,
-                daytime_cloud_color_index,
-                nighttime_cloud_color_index,  # SKY_CLOUD_COLORS
-                skip_harp_playing,
-                0xFF,
-                0xFF,
-                0xFF,
-            ],
+            SUBSDK_RANDOMIZER_SETTINGS_OFFSET: list(randomizer_settings),
             0x712E5FF020: [
                 0xFF,
                 0xFF,
@@ -543,6 +674,19 @@ class ASMPatchHandler:
                 0x00,
                 0x00,
             ],  # COLOR_CHANGE_DELAY
+            0x712E5FF038: [0x00],  # SKIP_ENTRANCE_HISTORY
+            0x712E5FF03C: [0x00, 0x00, 0x00, 0x00],  # BUTTON_COMBO_HELD_FRAMES
+            SUBSDK_MULTIWORLD_MAILBOX_OFFSET: list(
+                empty_mailbox()
+            ),  # MULTIWORLD_MAILBOX
+            SUBSDK_LOCATION_LOG_OFFSET: list(empty_location_log()),  # LOCATION_LOG
+            SUBSDK_EVENT_LOCATION_ID_OFFSET: [
+                0x00,
+                0x00,
+                0x00,
+                0x00,
+            ],  # EVENT_LOCATION_ID
+            SUBSDK_TRACKER_STATE_OFFSET: list(empty_tracker_state()),  # TRACKER_STATE
         }
 
         yaml_write(output_path, init_rw_globals_dict)
@@ -567,6 +711,17 @@ class ASMPatchHandler:
 
         yaml_write(output_path, damage_multiplier_dict)
 
+    def _write_table(self, output_path: Path, offset: int, data: bytes):
+        yaml_write(output_path, {offset: list(data)})
+
+    def _get_start_spawn_info(self, world: World) -> dict:
+        try:
+            return world.get_entrance(
+                "Link's Spawn -> Knight Academy"
+            ).replaces.spawn_info[0]
+        except:
+            return world.get_entrance("Link's Spawn -> Knight Academy").spawn_info[0]
+
     def _get_flags(
         self, startflag_section, onlyif_handler: ConditionalPatchHandler
     ) -> tuple:
@@ -1,7 +1,10 @@
 import logging
 import random
+from constants.asmconstants import ACTOR_LOCATION_FLAG_NONE
 from constants.itemconstants import CTMC_ITEMS_TO_FILTER_OUT, ITEMS_NOT_TO_TRAP
 from constants.patchconstants import (
+    CLOSET_SCENEFLAGS,
+    SQUIRREL_SCENEFLAGS,
     STAGE_PATCH_PATH_REGEX,
     EVENT_PATCH_PATH_REGEX,
     OARC_ADD_PATH_REGEX,
@@ -19,19 +22,10 @@ def determine_check_patches(
     stage_patch_handler: StagePatchHandler,
     event_patch_handler: EventPatchHandler,
 ):
-    # Custom flags currently use 10 total bits as follows
-    # in order of most significant to least significant bits:
-
-    # - (1 bit) flag_space_trigger: Used to denote whether this flag is using
-    # the unused scene flag space or the unused dungeon flag space
-
-    # - (2 bits) scene index / dungeon index: Used to denote which unused scene/dungeon index the
-    # flag is in. There are a total of 4 unused indexes for both scene flags and dungeon flags.
-    # Since each index can hold 128 bits, this gives us a total of 1,024 flags to use.
-
-    # - (7 bits) flag: The flag within the unused flag space. Can be any value from 0-127.
-    # 128 is used to indicate there being no custom flag (so really we can have up to 1,016 flags)
-    custom_flags = [i for i in range(1024) if (i & 0x7F) != 0x7F]
+    # Custom flags are location flags, the randomizer's own flags stored in the
+    # save (see "Location flags" in flag.rs). Item actors and tgreacts only have
+    # 10 bits of param2 for them so use the ids below ACTOR_LOCATION_FLAG_NONE
+    custom_flags = list(range(ACTOR_LOCATION_FLAG_NONE))
     custom_flags.reverse()
 
     location_table = world.location_table
@@ -47,7 +41,7 @@ def determine_check_patches(
         item = location.current_item
 
         # Deal with items with custom flags
-        custom_flag = 0x3FF  # Value for no custom flag
+        custom_flag = ACTOR_LOCATION_FLAG_NONE
         original_itemid = 0
 
         if "Custom Flag" in location.types:
@@ -167,7 +161,7 @@ def determine_check_patches(
                             tbox_subtype = 1
                         elif item.is_boss_key:
                             tbox_subtype = 2
-                        elif item.is_dungeon_small_key:
+                        elif item.is_dungeon_small_key or item.is_key_ring:
                             if (
                                 world.setting("small_keys_in_fancy_chests").value()
                                 == "on"
@@ -209,6 +203,46 @@ def determine_check_patches(
                     stage_patch_handler.add_oarc_for_check(stage, layer, oarc)
 
 
+def get_randomized_sceneflags(world: World) -> list[tuple[str, int]]:
+    """
+    Returns the stage and sceneflag of every randomized location whose item
+    keeps a sceneflag of its own, so the game only logs those (see
+    is_randomized_sceneflag in tracker.rs). Locations with a custom flag are
+    logged by that instead.
+    """
+    sceneflags = set()
+
+    for location in world.location_table.values():
+        if "Custom Flag" in location.types:
+            continue
+
+        if (
+            "Closets" in location.types
+            and world.setting("npc_closet_shuffle") == "vanilla"
+        ):
+            continue
+
+        for path in location.patch_paths:
+            if stage_patch_match := STAGE_PATCH_PATH_REGEX.match(path):
+                stage = stage_patch_match.group("stage")
+                room = int(stage_patch_match.group("room"))
+                object_name = stage_patch_match.group("objectName")
+                objectid = stage_patch_match.group("objectID")
+
+                # Freestanding items and digspots without a custom flag are
+                # found by their sceneflag
+                if object_name in ("Item", "Soil"):
+                    sceneflags.add((stage, int(objectid, 0)))
+                elif object_name == "MssbTag":
+                    sceneflags.add((stage, SQUIRREL_SCENEFLAGS[int(objectid, 16)]))
+                elif object_name == "chest":
+                    sceneflags.add(
+                        (stage, CLOSET_SCENEFLAGS[(stage, room, int(objectid, 16))])
+                    )
+
+    return sorted(sceneflags)
+
+
 def append_dungeon_item_patches(event_patch_handler: EventPatchHandler):
     print_progress_text("Creating Dungeon Item Patches")
 
@@ -227,6 +261,12 @@ def append_dungeon_item_patches(event_patch_handler: EventPatchHandler):
         211,
         212,
         213,
+        216,
+        217,
+        218,
+        219,
+        220,
+        221,
     ]
 
     # Patch the pre-existing entry for the Skyview Small Key (003_200).
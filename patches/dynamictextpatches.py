@@ -1,5 +1,7 @@
+from filepathconstants import BIRD_STATUE_DATA_PATH, WARP_DESTINATIONS_DATA_PATH
 from patches.eventpatchhandler import *
 from logic.world import *
+from util.warp_destinations import get_warp_destinations
 
 
 def add_dynamic_text_patches(
@@ -8,6 +10,7 @@ def add_dynamic_text_patches(
     print_progress_text("Adding Text Patches")
 
     add_fi_text_patches(world, event_patch_handler)
+    add_fi_warp_patches(world, event_patch_handler)
     add_gossip_stone_text_patches(world, event_patch_handler)
 
     if world.impa_sot_hint:
@@ -278,6 +281,110 @@ def add_fi_text_patches(world: World, event_patch_handler: EventPatchHandler) ->
     #         )
 
 
+def add_fi_warp_patches(world: World, event_patch_handler: EventPatchHandler) -> None:
+    warp_destinations = get_warp_destinations(
+        yaml_load(WARP_DESTINATIONS_DATA_PATH),
+        yaml_load(BIRD_STATUE_DATA_PATH),
+        lambda entrance: world.get_entrance(entrance).spawn_info[0],
+    )
+
+    # The index of a destination is also its index in the table written by
+    # ASMPatchHandler.patch_warp_destinations
+    groups: dict[str, list[tuple[Text, str]]] = {}
+    for index, destination in enumerate(warp_destinations):
+        event_patch_handler.append_to_event_patches(
+            "006-8KenseiNormal",
+            {
+                "name": f"Fi Warp to {destination.name}",
+                "type": "flowadd",
+                "flow": {
+                    "type": "type3",
+                    "next": "Fi Warp Locked Switch",
+                    "param1": index,
+                    "param3": 76,  # Custom command
+                },
+            },
+        )
+
+        if destination.group not in groups:
+            groups[destination.group] = []
+        groups[destination.group].append(
+            (Text(destination.name), f"Fi Warp to {destination.name}")
+        )
+
+    warp_choices = [(get_text_data("Fi Warp Start Choice"), "Warp to Start")]
+    for group, choices in groups.items():
+        add_fi_warp_menu(event_patch_handler, f"Fi Warp {group}", choices)
+        warp_choices.append((Text(group), f"Display Fi Warp {group} 0"))
+    warp_choices.append((get_text_data("Fi Warp Amiibo Choice"), 28))
+
+    add_fi_warp_menu(event_patch_handler, "Fi Warp Menu", warp_choices)
+
+
+def add_fi_warp_menu(
+    event_patch_handler: EventPatchHandler,
+    menu_name: str,
+    choices: list[tuple[Text, str | int]],
+) -> None:
+    # Text boxes only fit 4 choices, so every page but the last uses its last
+    # choice to go to the next page
+    pages = [choices[i : i + 3] for i in range(0, len(choices), 3)]
+
+    for page_index, page in enumerate(pages):
+        is_last_page = page_index == len(pages) - 1
+        last_choice = get_text_data(
+            "Fi Warp Never Mind Choice" if is_last_page else "Fi Warp More Choice"
+        )
+
+        choices_text = Text()
+        for lang in Text.SUPPORTED_LANGUAGES:
+            choices_text.text[lang] = "".join(
+                f"[{choice_index + 1}]{choice.get(lang)}"
+                for choice_index, (choice, _) in enumerate(page)
+            )
+            choices_text.text[lang] += f"[{len(page) + 1}-]{last_choice.get(lang)}"
+
+        event_patch_handler.append_to_event_patches(
+            "006-8KenseiNormal",
+            {
+                "name": f"Display {menu_name} {page_index}",
+                "type": "flowadd",
+                "flow": {
+                    "type": "type1",
+                    "next": f"{menu_name} Switch {page_index}",
+                    "param3": 68,
+                    "param4": f"{menu_name} Text {page_index}",
+                },
+            },
+        )
+        event_patch_handler.append_to_event_patches(
+            "006-8KenseiNormal",
+            {
+                "name": f"{menu_name} Switch {page_index}",
+                "type": "switchadd",
+                "flow": {
+                    "subType": 6,
+                    "param2": 0,
+                    "param3": 0,
+                },
+                "cases": [target for _, target in page]
+                + [-1 if is_last_page else f"Display {menu_name} {page_index + 1}"],
+            },
+        )
+        event_patch_handler.append_to_event_patches(
+            "006-8KenseiNormal",
+            {
+                "name": f"{menu_name} Text {page_index}",
+                "type": "textadd",
+                "textboxtype": 2,
+            },
+        )
+        add_text_data(
+            f"{menu_name} Text {page_index}",
+            get_text_data("Fi Warp Menu Text").replace("{choices}", choices_text),
+        )
+
+
 def add_gossip_stone_text_patches(
     world: World, event_patch_handler: EventPatchHandler
 ) -> None:
@@ -6,11 +6,13 @@ from constants.patchconstants import (
     PARAM1_ALIASES,
     PARAM2_ALIASES,
     DEFAULT_FLOW_TYPE_LOOKUP,
+    DEFAULT_EVENT_LOCATION_FLOW,
 )
 
 from filepathconstants import (
     EVENT_FILE_PATH_TAILS,
     EVENT_PATCHES_PATH,
+    PROGRESSIVE_ITEMS_DATA_PATH,
     VANILLA_EVENT_FILE_PATHS,
 )
 
@@ -202,6 +204,22 @@ class EventPatchHandler:
                             # Give item command == 9
                             parsed_msbf["FLW3"]["flow"][eventid]["param3"] = 9
 
+                            # Record which location this is before giving the item
+                            # so the tracker can log it alongside the item. Event
+                            # ids need the file number, so checks in unnumbered
+                            # files just aren't logged
+                            if not msbf_file_name[:3].isnumeric():
+                                continue
+
+                            flows = parsed_msbf["FLW3"]["flow"]
+                            flows.append(flows[eventid])
+
+                            event_location = DEFAULT_EVENT_LOCATION_FLOW.copy()
+                            event_location["param1"] = int(msbf_file_name[:3])
+                            event_location["param2"] = eventid
+                            event_location["next"] = len(flows) - 1
+                            flows[eventid] = event_location
+
                     if msbf_file_name == "003-ItemGet.msbf":
                         handle_progressive_items(parsed_msbf)
 
@@ -443,86 +461,16 @@ def make_progressive_item_events(
 
 
 def handle_progressive_items(msbf: ParsedMsb):
-    # Progressive Mitts
-    make_progressive_item_events(
-        msbf,
-        93,
-        (35, 231),
-        ITEM_ITEMFLAGS[PROGRESSIVE_MITTS],
-        ITEM_STORYFLAGS[PROGRESSIVE_MITTS],
-    )
-
-    # Progressive Swords
-    make_progressive_item_events(
-        msbf,
-        136,
-        (77, 608, 75, 78, 74, 73),
-        ITEM_ITEMFLAGS[PROGRESSIVE_SWORD],
-        ITEM_STORYFLAGS[PROGRESSIVE_SWORD],
-    )
-
-    # Progressive Beetles
-    make_progressive_item_events(
-        msbf,
-        96,
-        (38, 178, 177, 176),
-        ITEM_ITEMFLAGS[PROGRESSIVE_BEETLE],
-        ITEM_STORYFLAGS[PROGRESSIVE_BEETLE],
-    )
-
-    # Progressive Bows
-    make_progressive_item_events(
-        msbf,
-        127,
-        (68, 163, 162),
-        ITEM_ITEMFLAGS[PROGRESSIVE_BOW],
-        ITEM_STORYFLAGS[PROGRESSIVE_BOW],
-    )
-
-    # Progressive Slingshots
-    make_progressive_item_events(
-        msbf,
-        97,
-        (39, 237),
-        ITEM_ITEMFLAGS[PROGRESSIVE_SLINGSHOT],
-        ITEM_STORYFLAGS[PROGRESSIVE_SLINGSHOT],
-    )
-
-    # Progressive Bug Nets
-    make_progressive_item_events(
-        msbf,
-        20,
-        (18, 309),
-        ITEM_ITEMFLAGS[PROGRESSIVE_BUG_NET],
-        ITEM_STORYFLAGS[PROGRESSIVE_BUG_NET],
-    )
-
-    # Progressive Pouches
-    make_progressive_item_events(
-        msbf,
-        258,
-        (254, 253, 253, 253, 253),
-        ITEM_ITEMFLAGS[PROGRESSIVE_POUCH],
-        ITEM_STORYFLAGS[PROGRESSIVE_POUCH],
-    )
-
-    # Progressive Wallets
-    make_progressive_item_events(
-        msbf,
-        250,
-        (246, 245, 244, 255),
-        ITEM_ITEMFLAGS[PROGRESSIVE_WALLET],
-        ITEM_STORYFLAGS[PROGRESSIVE_WALLET],
-    )
-
-    # Song of the Hero parts
-    make_progressive_item_events(
-        msbf,
-        474,
-        (472, 471, 475, 477),
-        ITEM_ITEMFLAGS[SOTH_PART],
-        ITEM_STORYFLAGS[SOTH_PART],
-    )
+    # See data/progressive_items.yaml
+    for chain in yaml_load(PROGRESSIVE_ITEMS_DATA_PATH):
+        stages = chain["stages"]
+        make_progressive_item_events(
+            msbf,
+            chain["itemget_flow"],
+            tuple(stage["text"] for stage in stages),
+            [stage["itemid"] for stage in stages],
+            [stage["storyflag"] for stage in stages],
+        )
 
 
 def entrypoint_hash(name: str, entries: int) -> int:
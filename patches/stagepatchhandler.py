@@ -19,6 +19,7 @@ import multiprocessing as mp
 
 from constants.tboxsubtypes import VANILLA_TBOX_SUBTYPES
 from constants.patchconstants import (
+    CLOSET_SCENEFLAGS,
     DEFAULT_PATH,
     DEFAULT_PNT,
     DEFAULT_SOBJ,
@@ -30,6 +31,7 @@ from constants.patchconstants import (
     STAGE_OBJECT_NAMES,
     STAGE_FILE_REGEX,
     ROOM_ARC_REGEX,
+    SQUIRREL_SCENEFLAGS,
     VALID_STAGE_PATCH_TYPES,
 )
 
@@ -188,31 +190,9 @@ def patch_closet(
         # Makes sure the bit is set if not a trap
         closet["params2"] = mask_shift_set(closet["params2"], 0xF, 4, 0xF)
 
-    # Mapping of each closet (scene, roomid, objectid) to the local scene flag we'll use
-    unused_scene_flags: dict[tuple[str, int, int], int] = {
-        ("F001r", 1, 0xFC08): 12,  # Link's Closet            0x10
-        ("F001r", 1, 0xFC07): 24,  # Fledge's Closet          2x01
-        ("F001r", 6, 0xFC07): 32,  # Zelda's Closet           5x01 (vanilla scene flag)
-        ("F001r", 2, 0xFC03): 48,  # Groose's Closet          7x01
-        ("F001r", 5, 0xFC05): 50,  # Owlan's Closet           7x04
-        ("F001r", 4, 0xFC03): 57,  # Horwell's Closet         6x02
-        ("F001r", 6, 0xFC06): 61,  # Karane's Closet          6x20
-        ("F005r", 0, 0xFC0E): 81,  # O&P's Closet             Bx02
-        ("F006r", 0, 0xFC16): 82,  # Wryna's Closet           Bx04
-        ("F013r", 0, 0xFC0E): 100,  # Sparrot's Closet        Dx10
-        ("F014r", 0, 0xFC12): 101,  # Luv and Bertie's Closet Dx20
-        ("F015r", 0, 0xFC10): 105,  # Gondo's Closet          Cx02
-        ("F016r", 0, 0xFC28): 107,  # Mallara's Closet        Cx04
-        ("F017r", 0, 0xFC1E): 116,  # Rupin's Closet          Fx10
-        ("F018r", 0, 0xFC11): 117,  # Peater's Closet         Fx20
-        ("F018r", 0, 0xFC12): 118,  # Peatrice's Closet       Fx40
-        ("F011r", 0, 0xFC3B): 3,  # Pumm and Kina's Closet    1x08
-        ("F301_5", 0, 0xFC0C): 0,  # Skipper's Closet         2x01
-    }
-
     # Specify which scene flag to use
     closet["params1"] = mask_shift_set(
-        closet["params1"], 0xFF, 0, unused_scene_flags[(stage, room, id)]
+        closet["params1"], 0xFF, 0, CLOSET_SCENEFLAGS[(stage, room, id)]
     )
     # Patch in the item
     closet["params1"] = mask_shift_set(closet["params1"], 0xFF, 8, itemid)
@@ -344,13 +324,8 @@ def patch_squirrels(bzs: dict, itemid: int, object_id_str: str, trapid: int):
 
     squirrel_tag["params2"] = mask_shift_set(squirrel_tag["params2"], 0xFF, 0, itemid)
 
-    squirrel_id_to_sceneflag = {
-        0xFCC8: 88,  # 0xA 01
-        0xFC9C: 89,  # 0xA 02
-        0xFCA0: 90,  # 0xA 04
-    }
     squirrel_tag["params2"] = mask_shift_set(
-        squirrel_tag["params2"], 0xFF, 8, squirrel_id_to_sceneflag[id]
+        squirrel_tag["params2"], 0xFF, 8, SQUIRREL_SCENEFLAGS[id]
     )
 
 
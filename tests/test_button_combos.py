@@ -0,0 +1,46 @@
+import os
+import struct
+import sys
+
+import yaml
+
+ROOT_PATH = os.path.abspath(os.path.join(os.path.dirname(__file__), ".."))
+sys.path.insert(0, ROOT_PATH)
+
+from util.button_combos import (
+    BUTTON_COMBO_FORMAT,
+    BUTTON_COMBO_OPTIONS,
+    BUTTON_COMBO_SIZE,
+    pack_button_combo,
+)
+
+
+def test_button_combo_packing() -> None:
+    assert BUTTON_COMBO_SIZE == 8
+
+    # L-Stick + A + R, the combo from before it was configurable
+    buttons, hold_frames, enabled = struct.unpack(
+        BUTTON_COMBO_FORMAT, pack_button_combo("l_stick_a_r", 30)
+    )
+    assert buttons == 0x10000 | 0x800 | 0x40000
+    assert hold_frames == 30
+    assert enabled
+
+    buttons, hold_frames, enabled = struct.unpack(
+        BUTTON_COMBO_FORMAT, pack_button_combo("disabled", 0)
+    )
+    assert buttons == 0
+    assert not enabled
+
+
+def test_button_combo_settings_have_masks() -> None:
+    with open(
+        os.path.join(ROOT_PATH, "data", "settings_list.yaml"), encoding="utf-8"
+    ) as settings_file:
+        settings = {
+            setting["name"]: setting for setting in yaml.safe_load(settings_file)
+        }
+
+    for setting_name in ("soft_reset_combo", "back_in_time_combo"):
+        for option in settings[setting_name]["options"]:
+            assert next(iter(option)) in BUTTON_COMBO_OPTIONS
@@ -0,0 +1,78 @@
+import os
+import re
+import sys
+
+import yaml
+
+ROOT_PATH = os.path.abspath(os.path.join(os.path.dirname(__file__), ".."))
+sys.path.insert(0, ROOT_PATH)
+
+from constants.asmconstants import CUSTOM_ITEMIDS
+
+GROUP_OF_TADTONES_ITEMID = 214
+TADTONE_GROUP_COUNTER = 953
+
+
+def load_yaml(*path: str):
+    with open(os.path.join(ROOT_PATH, *path), encoding="utf-8") as file:
+        return yaml.safe_load(file)
+
+
+def read_custom_item_registry() -> str:
+    with open(
+        os.path.join(
+            ROOT_PATH, "asm", "additions", "rust-additions", "src", "customitem.rs"
+        ),
+        encoding="utf-8",
+    ) as file:
+        source = file.read()
+    return source[source.index("static CUSTOM_ITEMS") :]
+
+
+ITEM_GET_PATCHES = load_yaml("data", "patches", "eventpatches.yaml")["003-ItemGet"]
+
+
+def get_item_get_flows(itemid: int) -> list:
+    patches_by_name = {patch["name"]: patch for patch in ITEM_GET_PATCHES}
+    entry = next(
+        patch
+        for patch in ITEM_GET_PATCHES
+        if patch["type"] == "entryadd" and patch["entry"]["name"] == f"003_{itemid}"
+    )
+
+    flows = []
+    next_flow = entry["entry"]["value"]
+    while next_flow in patches_by_name:
+        flow = patches_by_name[next_flow]
+        flows.append(flow)
+        next_flow = flow["flow"].get("next", -1)
+    return flows
+
+
+def test_tadtone_counter_is_incremented_once_per_give() -> None:
+    # The item get event runs for every give, not only freestanding pickups,
+    # so the counter has to be incremented there before the groups left are
+    # read by command 75
+    assert GROUP_OF_TADTONES_ITEMID in CUSTOM_ITEMIDS
+    flows = get_item_get_flows(GROUP_OF_TADTONES_ITEMID)
+    commands = [flow["flow"].get("param3") for flow in flows]
+    increments = [
+        flow
+        for flow in flows
+        if flow["flow"].get("param3") == 74
+        and flow["flow"].get("param1") == TADTONE_GROUP_COUNTER
+    ]
+
+    assert len(increments) == 1
+    assert increments[0]["flow"]["param2"] == 1
+    assert commands.index(74) < commands.index(75)
+
+    # The collect handler in the registry only runs for freestanding pickups
+    # and mustn't count the group a second time
+    tadtone_entry = re.search(
+        rf"itemid:\s*{GROUP_OF_TADTONES_ITEMID},.*?on_collect:\s*(\w+)",
+        read_custom_item_registry(),
+        re.DOTALL,
+    )
+    assert tadtone_entry is not None
+    assert tadtone_entry.group(1) == "do_nothing"
@@ -0,0 +1,121 @@
+import os
+import struct
+import sys
+
+import yaml
+
+ROOT_PATH = os.path.abspath(os.path.join(os.path.dirname(__file__), ".."))
+sys.path.insert(0, ROOT_PATH)
+
+from constants.asmconstants import MAX_DUNGEON_ITEMS
+from constants.itemconstants import ITEM_COUNTS, KEY_RING_SMALL_KEYS
+from util.dungeon_items import (
+    DUNGEON_ITEM_FORMAT,
+    DUNGEON_ITEM_SIZE,
+    DungeonItemError,
+    pack_dungeon_items,
+)
+from util.flags import FLAG_REF_FORMAT, FLAG_SPACE_DUNGEONFLAG, FLAG_SPACE_STORYFLAG
+
+
+def load_yaml(*path: str):
+    with open(os.path.join(ROOT_PATH, *path), encoding="utf-8") as file:
+        return yaml.safe_load(file)
+
+
+ENTRIES = load_yaml("data", "dungeon_items.yaml")
+
+
+def unpack_entry(packed: bytes, index: int = 0) -> tuple:
+    itemid, amount, flag = struct.unpack_from(
+        DUNGEON_ITEM_FORMAT, packed, index * DUNGEON_ITEM_SIZE
+    )
+    return (itemid, amount, struct.unpack(FLAG_REF_FORMAT, flag))
+
+
+def get_entry(itemid: int) -> tuple:
+    packed = pack_dungeon_items(ENTRIES)
+    index = next(i for i, entry in enumerate(ENTRIES) if entry["id"] == itemid)
+    return unpack_entry(packed, index)
+
+
+def test_dungeon_items_size() -> None:
+    # Matches DungeonItem in item.rs
+    assert DUNGEON_ITEM_SIZE == 8
+    assert len(pack_dungeon_items(ENTRIES)) == MAX_DUNGEON_ITEMS * DUNGEON_ITEM_SIZE
+
+    # The table ends at the first empty entry
+    assert unpack_entry(pack_dungeon_items(ENTRIES), len(ENTRIES))[0] == 0
+
+
+def test_vanilla_dungeon_items() -> None:
+    # SV Boss Key, dungeonflag 7 of Skyview Temple
+    assert get_entry(29) == (29, 0, (FLAG_SPACE_DUNGEONFLAG, 11, 7))
+    # Caves Small Key, counted in the Lanayru Gorge scene
+    assert get_entry(206) == (206, 1, (FLAG_SPACE_DUNGEONFLAG, 9, 1))
+    # SK Map, dungeonflag 1 of Sky Keep
+    assert get_entry(213) == (213, 0, (FLAG_SPACE_DUNGEONFLAG, 20, 1))
+
+
+def test_key_rings_give_every_small_key() -> None:
+    small_key_counts = {
+        sceneindex: count
+        for item, (sceneindex, _, count) in ITEM_COUNTS.items()
+        if item in KEY_RING_SMALL_KEYS.values()
+    }
+
+    key_rings = [entry for entry in ENTRIES if entry["kind"] == "key_ring"]
+    assert len(key_rings) == len(KEY_RING_SMALL_KEYS)
+
+    for entry in key_rings:
+        _, amount, (_, sceneindex, _) = get_entry(entry["id"])
+        assert amount == small_key_counts[sceneindex]
+
+
+def test_new_dungeon_items() -> None:
+    entries = [
+        {
+            "id": 222,
+            "kind": "compass",
+            "flag": {"dungeonflag": {"scene": "Earth Temple", "flag": 2}},
+        },
+        {"id": 223, "kind": "dungeon_reward", "counter": {"storyflag": 953}},
+        {"id": 224, "kind": "dungeon_completed", "flag": {"storyflag": 7}},
+    ]
+    packed = pack_dungeon_items(entries)
+
+    assert unpack_entry(packed, 0) == (222, 0, (FLAG_SPACE_DUNGEONFLAG, 14, 2))
+    assert unpack_entry(packed, 1) == (223, 1, (FLAG_SPACE_STORYFLAG, 0, 953))
+    assert unpack_entry(packed, 2) == (224, 0, (FLAG_SPACE_STORYFLAG, 0, 7))
+
+
+def test_bad_dungeon_items_are_refused() -> None:
+    sky_keep_counter = {"dungeonflag": {"scene": "Sky Keep", "flag": 2}}
+    too_many = [
+        {"id": itemid, "kind": "dungeon_completed", "flag": {"storyflag": 7}}
+        for itemid in range(1, MAX_DUNGEON_ITEMS + 1)
+    ]
+
+    for entries in (
+        [{"id": 222, "kind": "lantern", "flag": {"storyflag": 7}}],
+        [{"id": 222, "kind": "dungeon_reward"}],
+        [{"id": 222, "kind": "map", "counter": sky_keep_counter}],
+        [{"id": 222, "kind": "compass", "flag": {"storyflag": 7}}],
+        [{"id": 222, "kind": "small_key", "counter": sky_keep_counter}],
+        [
+            {
+                "id": 222,
+                "kind": "dungeon_reward",
+                "counter": {"storyflag": 953},
+                "amount": 0,
+            }
+        ],
+        [{"id": 0, "kind": "dungeon_reward", "flag": {"storyflag": 7}}],
+        [ENTRIES[0], ENTRIES[0]],
+        too_many,
+    ):
+        try:
+            pack_dungeon_items(entries)
+        except DungeonItemError:
+            continue
+        assert False, "Bad dungeon items should have been refused"
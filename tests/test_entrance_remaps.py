@@ -0,0 +1,52 @@
+import os
+import struct
+import sys
+
+sys.path.insert(0, os.path.abspath(os.path.join(os.path.dirname(__file__), "..")))
+
+from constants.asmconstants import MAX_ENTRANCE_REMAPS
+from util.entrance_remaps import (
+    ENTRANCE_REMAP_FORMAT,
+    ENTRANCE_REMAP_SIZE,
+    EntranceRemapError,
+    pack_entrance_remaps,
+)
+
+
+def test_entrance_remap_packing() -> None:
+    packed_remaps = pack_entrance_remaps(
+        [
+            {
+                "from": {"stage": "D100", "room": 0, "entrance": 0},
+                "to": {"stage": "D101", "room": 0, "layer": 0, "entrance": 0},
+                "night": False,
+            }
+        ]
+    )
+
+    assert ENTRANCE_REMAP_SIZE == 24
+    assert len(packed_remaps) == MAX_ENTRANCE_REMAPS * ENTRANCE_REMAP_SIZE
+    assert struct.unpack_from(ENTRANCE_REMAP_FORMAT, packed_remaps) == (
+        b"D100\0\0\0\0",
+        0,
+        0xFF,  # any layer
+        0,
+        0,  # forced day
+        b"D101\0\0\0\0",
+        0,
+        0,
+        0,
+        0xFF,  # trial state isn't forced
+    )
+
+    # The table ends with an empty stage name
+    assert packed_remaps[ENTRANCE_REMAP_SIZE] == 0
+
+
+def test_entrance_remap_missing_fields() -> None:
+    try:
+        pack_entrance_remaps([{"from": {"stage": "D100"}, "to": {"stage": "D101"}}])
+    except EntranceRemapError:
+        return
+
+    assert False
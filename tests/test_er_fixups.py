@@ -0,0 +1,75 @@
+import os
+import struct
+import sys
+
+import yaml
+
+ROOT_PATH = os.path.abspath(os.path.join(os.path.dirname(__file__), ".."))
+sys.path.insert(0, ROOT_PATH)
+
+from constants.asmconstants import (
+    ER_FIXUP_RULE_SIZE,
+    MAX_ER_FIXUP_FLAG_RANGES,
+    MAX_ER_FIXUP_RULES,
+)
+from util.er_fixups import (
+    ER_FIXUP_FLAG_RANGE_FORMAT,
+    ER_FIXUP_FLAG_RANGE_SIZE,
+    ER_FIXUP_RULE_HEADER_FORMAT,
+    pack_er_fixup_rules,
+    pack_flag_ranges,
+)
+
+
+def load_yaml(*path: str):
+    with open(os.path.join(ROOT_PATH, *path), encoding="utf-8") as file:
+        return yaml.safe_load(file)
+
+
+ER_FIXUPS = load_yaml("data", "patches", "erfixups.yaml")
+
+
+def test_er_fixup_rule_size_matches_rust() -> None:
+    # stage + entrances, then the conditions and effects
+    assert ER_FIXUP_RULE_SIZE == (
+        struct.calcsize(ER_FIXUP_RULE_HEADER_FORMAT)
+        + 2 * MAX_ER_FIXUP_FLAG_RANGES * ER_FIXUP_FLAG_RANGE_SIZE
+    )
+
+
+def test_er_fixups_fit() -> None:
+    packed_rules = pack_er_fixup_rules(ER_FIXUPS)
+    rule_count = sum(len(rule["next_stage"]) for rule in ER_FIXUPS)
+
+    assert len(packed_rules) == MAX_ER_FIXUP_RULES * ER_FIXUP_RULE_SIZE
+    # The rule after the last one must be empty to end the table
+    assert not any(packed_rules[rule_count * ER_FIXUP_RULE_SIZE :])
+
+
+def test_er_fixup_rules_are_packed() -> None:
+    packed_rules = pack_er_fixup_rules(ER_FIXUPS)
+    stage, entrance, new_entrance = struct.unpack_from(
+        ER_FIXUP_RULE_HEADER_FORMAT, packed_rules
+    )
+
+    first_rule = ER_FIXUPS[0]
+    assert stage.rstrip(b"\0").decode() == first_rule["next_stage"][0][0]
+    assert entrance == first_rule["next_stage"][0][1]
+    assert new_entrance == first_rule.get("new_entrance", 0xFF)
+
+
+def test_consecutive_flags_are_merged() -> None:
+    flag_ranges = pack_flag_ranges(
+        [
+            {"storyflag": [5, 3, 4, 10], "value": True},
+            {"sceneflag": {"scene": "Skyloft", "flags": [7]}, "value": False},
+        ]
+    )
+
+    assert len(flag_ranges) == MAX_ER_FIXUP_FLAG_RANGES * ER_FIXUP_FLAG_RANGE_SIZE
+    assert [
+        struct.unpack_from(
+            ER_FIXUP_FLAG_RANGE_FORMAT, flag_ranges, i * ER_FIXUP_FLAG_RANGE_SIZE
+        )
+        for i in range(3)
+    ] == [(1, 1, 0, 3, 3), (1, 1, 0, 1, 10), (2, 0, 0, 1, 7)]
@@ -0,0 +1,61 @@
+import os
+import struct
+import sys
+
+ROOT_PATH = os.path.abspath(os.path.join(os.path.dirname(__file__), ".."))
+sys.path.insert(0, ROOT_PATH)
+
+from constants.asmconstants import SCENE_NAME_TO_SCENE_INDEX
+from util.flags import (
+    FLAG_REF_FORMAT,
+    FLAG_REF_SIZE,
+    FLAG_SPACE_NONE,
+    FLAG_SPACE_SCENEFLAG,
+    FLAG_SPACE_STAGE_OBJECT_FLAG,
+    FLAG_SPACE_STORYFLAG,
+    FLAG_SPACE_TEMPFLAG,
+    FLAG_SPACE_ZONEFLAG,
+    FlagRefError,
+    pack_flag_ref,
+    parse_flag_ref,
+)
+
+
+def test_flag_ref_packing() -> None:
+    assert FLAG_REF_SIZE == 4
+
+    space, sceneindex, flag = parse_flag_ref(
+        {"sceneflag": {"scene": "Skyloft", "flag": 29}}
+    )
+    assert space == FLAG_SPACE_SCENEFLAG
+    assert sceneindex == SCENE_NAME_TO_SCENE_INDEX["Skyloft"]
+    assert flag == 29
+    assert struct.unpack(FLAG_REF_FORMAT, pack_flag_ref(space, flag, sceneindex)) == (
+        space,
+        sceneindex,
+        flag,
+    )
+
+    assert parse_flag_ref({"storyflag": 22}) == (FLAG_SPACE_STORYFLAG, 0, 22)
+    assert parse_flag_ref(None) == (FLAG_SPACE_NONE, 0, 0)
+    assert parse_flag_ref({"tempflag": 3}) == (FLAG_SPACE_TEMPFLAG, 0, 3)
+    assert parse_flag_ref({"zoneflag": {"room": 2, "flag": 5}}) == (
+        FLAG_SPACE_ZONEFLAG,
+        2,
+        5,
+    )
+    assert parse_flag_ref(
+        {"stageobjectflag": {"scene": "Skyloft", "flag": 300}}
+    ) == (FLAG_SPACE_STAGE_OBJECT_FLAG, SCENE_NAME_TO_SCENE_INDEX["Skyloft"], 300)
+
+
+def test_unknown_flags_are_rejected() -> None:
+    for flag_ref in (
+        {"someflag": 1},
+        {"sceneflag": {"scene": "Not a Scene", "flag": 1}},
+    ):
+        try:
+            parse_flag_ref(flag_ref)
+        except FlagRefError:
+            continue
+        assert False, f"{flag_ref} should have been rejected"
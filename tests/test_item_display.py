@@ -0,0 +1,67 @@
+import os
+import struct
+import sys
+
+import yaml
+
+ROOT_PATH = os.path.abspath(os.path.join(os.path.dirname(__file__), ".."))
+sys.path.insert(0, ROOT_PATH)
+
+from constants.asmconstants import CUSTOM_ITEMIDS, ITEM_DISPLAY_INFO_COUNT
+from util.item_display import (
+    ITEM_DISPLAY_FLAG_DEFAULT_SCALING,
+    ITEM_DISPLAY_INFO_FORMAT,
+    ITEM_DISPLAY_INFO_SIZE,
+    ItemDisplayInfoError,
+    pack_item_display_info,
+)
+
+
+def load_yaml(*path: str):
+    with open(os.path.join(ROOT_PATH, *path), encoding="utf-8") as file:
+        return yaml.safe_load(file)
+
+
+ENTRIES = load_yaml("data", "item_display_info.yaml")
+TABLE = pack_item_display_info(ENTRIES)
+
+
+def get_entry(itemid: int) -> tuple:
+    return struct.unpack_from(
+        ITEM_DISPLAY_INFO_FORMAT, TABLE, itemid * ITEM_DISPLAY_INFO_SIZE
+    )
+
+
+def test_item_display_info_size() -> None:
+    # Matches ItemDisplayInfo in item.rs
+    assert ITEM_DISPLAY_INFO_SIZE == 24
+    assert len(TABLE) == ITEM_DISPLAY_INFO_COUNT * ITEM_DISPLAY_INFO_SIZE
+
+
+def test_item_display_info_entries() -> None:
+    # Unlisted items keep the defaults
+    assert get_entry(0) == (0.0, 0.0, 1.0, 1.0, 0, 0, 0, 0)
+
+    # Small Key
+    assert get_entry(1) == (0.0, 5.0, 1.0, 1.0, 0x0C00, 0x1000, 0x0600, 0)
+    # 5 Bombs
+    assert get_entry(40)[3] == 1.5
+    # Triforce of Courage
+    assert get_entry(95)[0] == 24.0
+    assert get_entry(95)[7] == ITEM_DISPLAY_FLAG_DEFAULT_SCALING
+    # Custom items use the registry in customitem.rs instead
+    assert get_entry(214) == get_entry(0)
+
+
+def test_bad_entries_are_refused() -> None:
+    for entries in (
+        [{"id": ITEM_DISPLAY_INFO_COUNT}],
+        [{"id": 1}, {"id": 1}],
+        [{"id": 1, "wall_rotation": [0x800]}],
+        [{"id": CUSTOM_ITEMIDS[0], "model_scale": 0.5}],
+    ):
+        try:
+            pack_item_display_info(entries)
+        except ItemDisplayInfoError:
+            continue
+        assert False, f"Bad display info should have been refused: {entries}"
@@ -6,6 +6,8 @@ sys.path.insert(0, os.path.abspath(os.path.join(os.path.dirname(__file__), "..")
 from constants.itemnames import *
 from logic.generate import generate
 from logic.config import *
+from logic.area import Area
+from logic.entrance import Entrance
 from logic.search import all_logic_satisfied
 from logic.world import World
 from filepathconstants import SPOILER_LOGS_PATH
@@ -123,6 +125,36 @@ def test_dungeon_items_anywhere() -> None:
     config_test("dungeon_items_anywhere.yaml")
 
 
+def test_small_key_rings() -> None:
+    worlds = config_test("small_key_rings.yaml")
+    world = worlds[0]
+    placed_items = [loc.current_item for loc in world.get_all_item_locations()]
+    assert world.get_item(SV_SMALL_KEY) not in placed_items
+    assert placed_items.count(world.get_item(SV_KEY_RING)) == 1
+
+    # Key Rings follow the Small Keys setting
+    skyview = world.get_dungeon("Skyview Temple")
+    assert any(loc.current_item == skyview.key_ring for loc in skyview.locations)
+
+
+def test_disconnect_skips_disconnected_conditional_connections() -> None:
+    # Adding the Key Ring setting changed max_entrance_rando's seed into one
+    # where the playthrough pare-down disconnects an entrance after one of
+    # its conditional vanilla connections was already found not required
+    area, conditional_area = Area(), Area()
+    area.name, conditional_area.name = "Area", "Conditional Area"
+    entrance = Entrance(connected_area_=area)
+    conditional_entrance = Entrance(connected_area_=conditional_area)
+    area.entrances.append(entrance)
+    conditional_area.entrances.append(conditional_entrance)
+    entrance.conditional_vanilla_connections.append(conditional_entrance)
+
+    assert conditional_entrance.disconnect() == conditional_area
+    assert entrance.disconnect() == area
+    assert conditional_entrance.connected_area is None
+    assert not area.entrances and not conditional_area.entrances
+
+
 def test_dungeon_items_removed() -> None:
     config_test("dungeon_items_removed.yaml")
 
@@ -0,0 +1,130 @@
+import os
+import sys
+
+sys.path.insert(0, os.path.abspath(os.path.join(os.path.dirname(__file__), "..")))
+
+from constants.asmconstants import (
+    MULTIWORLD_MAILBOX_CAPACITY,
+    MULTIWORLD_MAILBOX_STATUS_INVALID_ITEM,
+    MULTIWORLD_MAILBOX_STATUS_NO_SAVE_DATA,
+    MULTIWORLD_MAILBOX_STATUS_OK,
+)
+from util.multiworld import MailboxClient, MailboxGameMock
+
+
+def make_mailbox() -> tuple[MailboxGameMock, MailboxClient]:
+    game = MailboxGameMock()
+    client = MailboxClient(game.read, game.write)
+    return game, client
+
+
+def test_items_are_given_in_order_once() -> None:
+    game, client = make_mailbox()
+
+    for itemid in (9, 19, 52):
+        client.queue_item(itemid)
+
+    assert client.update()
+
+    for _ in range(10):
+        game.frame()
+
+    assert game.given_items == [9, 19, 52]
+    assert game.items_received == 3
+
+
+def test_items_wait_for_a_safe_moment() -> None:
+    game, client = make_mailbox()
+    client.queue_item(28)
+    client.update()
+
+    game.frame(can_receive_items=False)
+    assert game.given_items == []
+
+    game.frame()
+    assert game.given_items == [28]
+
+
+def test_reload_resends_without_duplicates() -> None:
+    game, client = make_mailbox()
+
+    for itemid in range(1, 6):
+        client.queue_item(itemid)
+
+    client.update()
+    for _ in range(5):
+        game.frame()
+
+    # Reload a save that was made after the 2nd item
+    game.reload(2)
+    game.frame()
+    client.update()
+
+    for _ in range(10):
+        game.frame()
+
+    assert game.given_items == [1, 2, 3, 4, 5]
+
+
+def test_more_items_than_capacity() -> None:
+    game, client = make_mailbox()
+    item_count = MULTIWORLD_MAILBOX_CAPACITY * 2 + 5
+
+    for itemid in range(item_count):
+        client.queue_item(itemid % 0x100)
+
+    for _ in range(item_count * 2):
+        client.update()
+        game.frame()
+
+    assert game.given_items == [itemid % 0x100 for itemid in range(item_count)]
+
+
+def test_bad_magic_is_ignored() -> None:
+    game, client = make_mailbox()
+    game.write(0, b"\0\0\0\0")
+    client.queue_item(9)
+
+    assert not client.update()
+    game.frame()
+
+    assert game.given_items == []
+
+
+def test_invalid_items_are_reported_and_kept() -> None:
+    game, client = make_mailbox()
+
+    for itemid in (9, 0x100, 52):
+        client.queue_item(itemid)
+
+    for _ in range(5):
+        client.update()
+        game.frame()
+
+    client.update()
+    assert game.given_items == [9]
+    assert game.items_received == 1
+    assert client.status == MULTIWORLD_MAILBOX_STATUS_INVALID_ITEM
+    assert client.rejected_index == 1
+
+    client.replace_item(1, 19)
+    for _ in range(5):
+        client.update()
+        game.frame()
+
+    client.update()
+    assert game.given_items == [9, 19, 52]
+    assert client.status == MULTIWORLD_MAILBOX_STATUS_OK
+
+
+def test_missing_save_data_is_reported() -> None:
+    game, client = make_mailbox()
+    game.has_save_data = False
+    client.queue_item(9)
+
+    client.update()
+    game.frame()
+    client.update()
+
+    assert game.given_items == []
+    assert client.status == MULTIWORLD_MAILBOX_STATUS_NO_SAVE_DATA
@@ -0,0 +1,56 @@
+import os
+import sys
+
+import yaml
+
+ROOT_PATH = os.path.abspath(os.path.join(os.path.dirname(__file__), ".."))
+sys.path.insert(0, ROOT_PATH)
+
+from constants.asmconstants import MAX_NIGHT_VALIDITY_RULES
+from util.night_validity import (
+    NIGHT_VALIDITY_RULE_SIZE,
+    get_day_only_areas,
+    is_valid_at_night,
+    pack_night_validity_rules,
+)
+
+
+def load_yaml(*path: str):
+    with open(os.path.join(ROOT_PATH, *path), encoding="utf-8") as file:
+        return yaml.safe_load(file)
+
+
+RULES = load_yaml("data", "night_validity.yaml")
+
+
+def test_night_validity_rules() -> None:
+    assert is_valid_at_night(RULES, "F000", 0)  # Skyloft
+    assert is_valid_at_night(RULES, "F001r", 5)  # Knight Academy
+    assert is_valid_at_night(RULES, "D000", 0)  # Waterfall Cave
+    assert is_valid_at_night(RULES, "S000", 0)  # Goddess's Silent Realm
+    assert is_valid_at_night(RULES, "F020", 22)  # Lumpy West Door
+
+    assert not is_valid_at_night(RULES, "F004r", 0)  # Bazaar
+    assert not is_valid_at_night(RULES, "F020", 1)  # Sky
+    assert not is_valid_at_night(RULES, "F023", 0)  # Thunderhead
+    assert not is_valid_at_night(RULES, "F100", 0)  # Faron Woods
+    assert not is_valid_at_night(RULES, "D100", 0)  # Skyview
+
+
+def test_day_only_areas() -> None:
+    day_only_areas = get_day_only_areas(
+        RULES, load_yaml("data", "entrance_shuffle_data.yaml")
+    )
+
+    assert "The Sky" in day_only_areas
+    assert "Inside the Thunderhead" in day_only_areas
+    assert "Sandship Main Deck" in day_only_areas
+    assert "Knight Academy" not in day_only_areas
+    assert "Waterfall Cave" not in day_only_areas
+
+
+def test_night_validity_rules_fit() -> None:
+    packed_rules = pack_night_validity_rules(RULES)
+    assert NIGHT_VALIDITY_RULE_SIZE == 16
+    assert len(packed_rules) == MAX_NIGHT_VALIDITY_RULES * NIGHT_VALIDITY_RULE_SIZE
+    assert packed_rules[len(RULES) * NIGHT_VALIDITY_RULE_SIZE] == 0
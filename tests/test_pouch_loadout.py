@@ -0,0 +1,72 @@
+import os
+import sys
+
+ROOT_PATH = os.path.abspath(os.path.join(os.path.dirname(__file__), ".."))
+sys.path.insert(0, ROOT_PATH)
+
+from util.pouch_loadout import (
+    ITEM_CHECK_SLOT_COUNT,
+    LOADOUT_STORAGE_ITEM_CHECK,
+    LOADOUT_STORAGE_POUCH,
+    LoadoutItem,
+    PouchLoadoutError,
+    get_loadout,
+    get_pouch_capacity,
+)
+from util.startflags import (
+    STARTFLAG_TAG_LOADOUT_ITEM,
+    StartflagRecord,
+    pack_startflags,
+    unpack_startflags,
+)
+
+BOTTLE = (153, 0)
+HEART_POTION = (78, 0)
+WOODEN_SHIELD = (116, 8)
+HYLIAN_SHIELD = (125, 0x30)
+
+
+def test_pouch_capacity() -> None:
+    assert get_pouch_capacity(0) == 0
+    assert get_pouch_capacity(1) == 4
+    assert get_pouch_capacity(5) == 8
+    assert get_pouch_capacity(9) == 8
+
+
+def test_shields_go_first() -> None:
+    loadout = get_loadout([BOTTLE, WOODEN_SHIELD, HEART_POTION, HYLIAN_SHIELD], 1)
+
+    assert loadout == [
+        LoadoutItem(LOADOUT_STORAGE_POUCH, 0, *HYLIAN_SHIELD),
+        LoadoutItem(LOADOUT_STORAGE_POUCH, 1, *WOODEN_SHIELD),
+        LoadoutItem(LOADOUT_STORAGE_POUCH, 2, *BOTTLE),
+        LoadoutItem(LOADOUT_STORAGE_POUCH, 3, *HEART_POTION),
+    ]
+
+
+def test_overflow_goes_to_item_check() -> None:
+    loadout = get_loadout([HYLIAN_SHIELD] + [BOTTLE] * 5, 1)
+
+    assert [item.storage for item in loadout] == [LOADOUT_STORAGE_POUCH] * 4 + [
+        LOADOUT_STORAGE_ITEM_CHECK
+    ] * 2
+    assert [item.slot for item in loadout[4:]] == [0, 1]
+
+    # Without the Adventure Pouch everything is in Item Check
+    loadout = get_loadout([HYLIAN_SHIELD, BOTTLE], 0)
+    assert all(item.storage == LOADOUT_STORAGE_ITEM_CHECK for item in loadout)
+
+
+def test_loadout_must_fit() -> None:
+    try:
+        get_loadout([BOTTLE] * (ITEM_CHECK_SLOT_COUNT + 5), 1)
+    except PouchLoadoutError:
+        return
+    assert False, "Oversized loadout should have been rejected"
+
+
+def test_loadout_round_trip() -> None:
+    loadout = get_loadout([HYLIAN_SHIELD, BOTTLE, HEART_POTION], 2)
+    records = [StartflagRecord(STARTFLAG_TAG_LOADOUT_ITEM, loadout)]
+
+    assert unpack_startflags(pack_startflags(records)) == (records, [])
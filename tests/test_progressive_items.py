@@ -0,0 +1,96 @@
+import os
+import struct
+import sys
+
+import yaml
+
+ROOT_PATH = os.path.abspath(os.path.join(os.path.dirname(__file__), ".."))
+sys.path.insert(0, ROOT_PATH)
+
+from constants.asmconstants import MAX_PROGRESSIVE_ITEM_CHAINS
+from constants.itemconstants import ITEM_ITEMFLAGS, ITEM_STORYFLAGS
+from util.flags import FLAG_REF_FORMAT, FLAG_SPACE_ITEMFLAG, FLAG_SPACE_STORYFLAG
+from util.progressive_items import (
+    PROGRESSIVE_ITEM_CHAIN_HEADER_FORMAT,
+    PROGRESSIVE_ITEM_CHAIN_SIZE,
+    PROGRESSIVE_ITEM_STAGE_FORMAT,
+    ProgressiveItemError,
+    pack_progressive_item_chains,
+)
+
+
+def load_yaml(*path: str):
+    with open(os.path.join(ROOT_PATH, *path), encoding="utf-8") as file:
+        return yaml.safe_load(file)
+
+
+CHAINS = load_yaml("data", "progressive_items.yaml")
+
+
+def get_chain(name: str) -> dict:
+    return next(chain for chain in CHAINS if chain["name"] == name)
+
+
+def test_chains_match_item_constants() -> None:
+    for chain in CHAINS:
+        stages = chain["stages"]
+        assert [stage["itemid"] for stage in stages] == ITEM_ITEMFLAGS[chain["name"]]
+        assert [stage["storyflag"] for stage in stages] == ITEM_STORYFLAGS[
+            chain["name"]
+        ]
+
+
+def test_progressive_item_chains_size() -> None:
+    # Matches ProgressiveItemChain in item.rs
+    assert PROGRESSIVE_ITEM_CHAIN_SIZE == 244
+    assert len(pack_progressive_item_chains(CHAINS)) == (
+        MAX_PROGRESSIVE_ITEM_CHAINS * PROGRESSIVE_ITEM_CHAIN_SIZE
+    )
+
+
+def test_progressive_item_stages() -> None:
+    pouch = get_chain("Progressive Pouch")
+    packed_chains = pack_progressive_item_chains([pouch])
+
+    assert struct.unpack_from(PROGRESSIVE_ITEM_CHAIN_HEADER_FORMAT, packed_chains) == (
+        112,
+        5,
+    )
+
+    flag, itemid, text, arcname, modelname = struct.unpack_from(
+        PROGRESSIVE_ITEM_STAGE_FORMAT,
+        packed_chains,
+        struct.calcsize(PROGRESSIVE_ITEM_CHAIN_HEADER_FORMAT),
+    )
+    assert struct.unpack(FLAG_REF_FORMAT, flag) == (FLAG_SPACE_ITEMFLAG, 0, 112)
+    assert (itemid, text) == (112, 254)
+    assert arcname.rstrip(b"\0") == b"GetPouchA"
+    assert modelname.rstrip(b"\0") == b"GetPorchA"
+
+    # Other flags can be used to tell if a stage is owned
+    chain = {
+        "name": "Custom Chain",
+        "itemid": 190,
+        "stages": [{"itemid": 190, "text": 472, "flag": {"storyflag": 895}}],
+    }
+    flag = struct.unpack_from(
+        PROGRESSIVE_ITEM_STAGE_FORMAT,
+        pack_progressive_item_chains([chain]),
+        struct.calcsize(PROGRESSIVE_ITEM_CHAIN_HEADER_FORMAT),
+    )[0]
+    assert struct.unpack(FLAG_REF_FORMAT, flag) == (FLAG_SPACE_STORYFLAG, 0, 895)
+
+
+def test_bad_chains_are_refused() -> None:
+    bow = get_chain("Progressive Bow")
+    for chains in (
+        [bow] * MAX_PROGRESSIVE_ITEM_CHAINS,
+        [{**bow, "stages": []}],
+        [{**bow, "stages": bow["stages"] * 3}],
+        [{**bow, "stages": [{**bow["stages"][0], "arcname": "A" * 16}]}],
+    ):
+        try:
+            pack_progressive_item_chains(chains)
+        except ProgressiveItemError:
+            continue
+        assert False, "Bad progressive item chains should have been refused"
@@ -0,0 +1,74 @@
+import os
+import struct
+import sys
+
+ROOT_PATH = os.path.abspath(os.path.join(os.path.dirname(__file__), ".."))
+sys.path.insert(0, ROOT_PATH)
+
+from constants.asmconstants import MAX_RANDOMIZER_SETTINGS_SIZE
+from util.button_combos import BUTTON_COMBO_SIZE, pack_button_combo
+from util.runtime_settings import (
+    GENERATED_END,
+    GENERATED_START,
+    RUST_SETTINGS_PATH,
+    generate_rust_settings,
+    get_randomizer_settings_format,
+    get_randomizer_settings_size,
+    load_runtime_settings_schema,
+    pack_randomizer_settings,
+)
+
+SCHEMA = load_runtime_settings_schema()
+
+
+class MockSetting:
+    def __init__(self, value: str, value_index: int) -> None:
+        self._value = value
+        self._value_index = value_index
+
+    def value(self) -> str:
+        return self._value
+
+    def value_index(self) -> int:
+        return self._value_index
+
+
+def get_mock_setting(name: str) -> MockSetting:
+    if name.endswith("_combo"):
+        return MockSetting("l_stick_a_r", 1)
+    if name.endswith("_hold_frames"):
+        return MockSetting("30", 30)
+    return MockSetting("on", 7)
+
+
+def test_rust_settings_match_schema() -> None:
+    # Regenerate with `python -m util.runtime_settings` if this fails
+    with open(RUST_SETTINGS_PATH, encoding="utf-8") as settings_file:
+        source = settings_file.read()
+
+    start = source.index(GENERATED_START)
+    end = source.index(GENERATED_END) + len(GENERATED_END)
+    assert source[start:end] == generate_rust_settings(SCHEMA)
+
+
+def test_randomizer_settings_packing() -> None:
+    packed_settings = pack_randomizer_settings(SCHEMA, get_mock_setting)
+
+    assert len(packed_settings) == get_randomizer_settings_size(SCHEMA)
+    assert len(packed_settings) <= MAX_RANDOMIZER_SETTINGS_SIZE
+    assert len(packed_settings) % 4 == 0
+
+    magic, version, size = struct.unpack_from("<4sHH", packed_settings)
+    assert magic == SCHEMA["magic"].encode("ascii")
+    assert version == SCHEMA["version"]
+    assert size == len(packed_settings)
+
+    values = struct.unpack(get_randomizer_settings_format(SCHEMA), packed_settings)
+    assert values[3:7] == (7, 7, 7, 7)  # sky_cloud_colors
+    assert values[7] == 7  # skip_harp_playing
+    assert values[8] == values[9] == pack_button_combo("l_stick_a_r", 30)
+
+    # Button combos contain a u32 so must stay 4-byte aligned
+    combos_offset = len(packed_settings) - 2 * BUTTON_COMBO_SIZE
+    assert combos_offset % 4 == 0
+    assert packed_settings[combos_offset:] == pack_button_combo("l_stick_a_r", 30) * 2
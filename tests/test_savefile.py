@@ -0,0 +1,48 @@
+import os
+import sys
+
+sys.path.insert(0, os.path.abspath(os.path.join(os.path.dirname(__file__), "..")))
+
+from constants.asmconstants import (
+    RANDO_SAVE_DATA_MAGIC,
+    SAVEFILE_RANDO_DATA_OFFSET,
+    SAVEFILE_RANDO_DATA_SIZE,
+    SAVEFILE_SIZE,
+)
+from util.savefile import SaveFileError, find_writes, get_rando_save_data, is_claimed
+
+
+def test_find_writes() -> None:
+    before = bytes(0x10 + SAVEFILE_SIZE)
+    after = bytearray(before)
+
+    # Outside of the randomizer's space
+    after[0x10 + SAVEFILE_RANDO_DATA_OFFSET - 1] = 1
+    after[0x10 + SAVEFILE_RANDO_DATA_OFFSET + SAVEFILE_RANDO_DATA_SIZE] = 1
+    assert find_writes(before, after, 0x10) == []
+
+    after[0x10 + SAVEFILE_RANDO_DATA_OFFSET : 0x10 + SAVEFILE_RANDO_DATA_OFFSET + 2] = (
+        b"\1\1"
+    )
+    after[0x10 + SAVEFILE_RANDO_DATA_OFFSET + SAVEFILE_RANDO_DATA_SIZE - 1] = 1
+    assert find_writes(before, after, 0x10) == [
+        (0, 2),
+        (SAVEFILE_RANDO_DATA_SIZE - 1, 1),
+    ]
+
+
+def test_rando_save_data() -> None:
+    dump = bytearray(SAVEFILE_SIZE)
+    assert not is_claimed(get_rando_save_data(dump))
+
+    dump[SAVEFILE_RANDO_DATA_OFFSET : SAVEFILE_RANDO_DATA_OFFSET + 6] = (
+        RANDO_SAVE_DATA_MAGIC + b"\1\0"
+    )
+    assert is_claimed(get_rando_save_data(dump))
+
+    try:
+        get_rando_save_data(dump, 1)
+    except SaveFileError:
+        pass
+    else:
+        assert False, "a dump too short for the save file was accepted"
@@ -0,0 +1,136 @@
+import os
+import struct
+import sys
+
+ROOT_PATH = os.path.abspath(os.path.join(os.path.dirname(__file__), ".."))
+sys.path.insert(0, ROOT_PATH)
+
+from constants.asmconstants import MAX_STARTFLAGS_SIZE
+from util.startflags import (
+    STARTFLAG_RECORD_HEADER_FORMAT,
+    STARTFLAG_TAG_COUNTER,
+    STARTFLAG_TAG_DUNGEONFLAG,
+    STARTFLAG_TAG_ITEMFLAG,
+    STARTFLAG_TAG_SCENEFLAG,
+    STARTFLAG_TAG_STAGE_OBJECT_FLAG,
+    STARTFLAG_TAG_STORYFLAG,
+    STARTFLAG_TAG_ZONEFLAG,
+    STARTFLAGS_HEADER_FORMAT,
+    STARTFLAGS_HEADER_SIZE,
+    StartflagRecord,
+    StartflagsError,
+    get_stage_object_flags,
+    pack_startflags,
+    unpack_startflags,
+)
+
+
+def test_startflags_round_trip() -> None:
+    records = [
+        StartflagRecord(STARTFLAG_TAG_STORYFLAG, [(11,), (13,), (951,)]),
+        StartflagRecord(STARTFLAG_TAG_SCENEFLAG, [(0, 29), (7, 91)]),
+        StartflagRecord(STARTFLAG_TAG_COUNTER, [(0x5E, 2), (11, 1)]),
+        StartflagRecord(
+            STARTFLAG_TAG_STAGE_OBJECT_FLAG,
+            [(b"F001r\0\0\0", 5), (b"F001r\0\0\0", 0xFFFF)],
+        ),
+    ]
+    packed_startflags = pack_startflags(records)
+
+    assert len(packed_startflags) == MAX_STARTFLAGS_SIZE
+    assert unpack_startflags(packed_startflags) == (records, [])
+
+
+def test_long_sections_are_split_into_records() -> None:
+    storyflags = [(flag,) for flag in range(300)]
+    records, skipped = unpack_startflags(
+        pack_startflags([StartflagRecord(STARTFLAG_TAG_STORYFLAG, storyflags)])
+    )
+
+    assert len(records) == 3
+    assert not skipped
+    assert sum((record.entries for record in records), []) == storyflags
+
+
+def test_startflags_must_fit() -> None:
+    try:
+        pack_startflags(
+            [StartflagRecord(STARTFLAG_TAG_STORYFLAG, [(0,)] * MAX_STARTFLAGS_SIZE)]
+        )
+    except StartflagsError:
+        return
+    assert False, "Oversized startflags should have been rejected"
+
+
+def test_bad_records_are_skipped() -> None:
+    packed_startflags = bytearray(
+        pack_startflags(
+            [
+                StartflagRecord(STARTFLAG_TAG_SCENEFLAG, [(0, 29), (26, 1)]),
+                StartflagRecord(STARTFLAG_TAG_DUNGEONFLAG, [(0xFF, 1)]),
+                StartflagRecord(STARTFLAG_TAG_ZONEFLAG, [(63, 1)]),
+                StartflagRecord(STARTFLAG_TAG_ITEMFLAG, [(512,)]),
+                StartflagRecord(STARTFLAG_TAG_COUNTER, [(0x5E, 2), (0xFFFF, 1)]),
+                StartflagRecord(STARTFLAG_TAG_STORYFLAG, [(22,)]),
+            ]
+        )
+    )
+
+    # Add a record with an unknown tag in front of the storyflag
+    magic, version, length = struct.unpack_from(
+        STARTFLAGS_HEADER_FORMAT, packed_startflags
+    )
+    unknown_record = struct.pack(STARTFLAG_RECORD_HEADER_FORMAT, 0x7F, 3) + b"abc"
+    storyflag_offset = STARTFLAGS_HEADER_SIZE + length - 4
+    packed_startflags[storyflag_offset:storyflag_offset] = unknown_record
+    struct.pack_into(
+        STARTFLAGS_HEADER_FORMAT,
+        packed_startflags,
+        0,
+        magic,
+        version,
+        length + len(unknown_record),
+    )
+
+    records, skipped = unpack_startflags(bytes(packed_startflags))
+
+    # The valid flags around the bad ones are still applied
+    assert records[0] == StartflagRecord(STARTFLAG_TAG_SCENEFLAG, [(0, 29)])
+    assert records[-1] == StartflagRecord(STARTFLAG_TAG_STORYFLAG, [(22,)])
+    assert skipped == [
+        "Skipping startflag with invalid scene: 26",
+        "Skipping startflag with invalid scene: 255",
+        "Skipping zoneflag with invalid room: 63",
+        "Skipping startflag with invalid itemflag: 512",
+        "Skipping startflag with invalid counter: 65535",
+        "Skipping unknown startflag tag: 127",
+    ]
+
+
+def test_stage_object_flags_only_apply_to_the_start_stage() -> None:
+    entries = get_stage_object_flags({"F001r": [5, 0x0401], "D100": [7]}, "F001r")
+    assert entries == [(b"F001r", 5), (b"F001r", 0x0401)]
+
+    # Written for a different start stage by mistake
+    packed_startflags = pack_startflags(
+        [StartflagRecord(STARTFLAG_TAG_STAGE_OBJECT_FLAG, entries)]
+    )
+    records, skipped = unpack_startflags(packed_startflags, "D100")
+
+    assert records == [StartflagRecord(STARTFLAG_TAG_STAGE_OBJECT_FLAG, [])]
+    assert skipped == [
+        "Skipping stage object flag for another stage: 5",
+        "Skipping stage object flag for another stage: 1025",
+    ]
+
+
+def test_unknown_version_is_refused() -> None:
+    packed_startflags = bytearray(
+        pack_startflags([StartflagRecord(STARTFLAG_TAG_STORYFLAG, [(22,)])])
+    )
+    struct.pack_into("<H", packed_startflags, 4, 1)
+
+    assert unpack_startflags(bytes(packed_startflags)) == (
+        [],
+        ["Unknown startflags version: 1"],
+    )
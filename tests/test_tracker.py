@@ -0,0 +1,239 @@
+import os
+import struct
+import sys
+
+sys.path.insert(0, os.path.abspath(os.path.join(os.path.dirname(__file__), "..")))
+
+from constants.asmconstants import (
+    ENTRANCE_HISTORY_CAPACITY,
+    LOCATION_LOG_CAPACITY,
+    MAX_RANDOMIZED_SCENEFLAGS,
+    SAVEFILE_ENTRANCE_HISTORY_OFFSET,
+    SAVEFILE_LOCATION_FLAGS_OFFSET,
+)
+from util.tracker import (
+    ENTRANCE_HISTORY_HEADER_SIZE,
+    ENTRANCE_HISTORY_SIZE,
+    ENTRANCE_TRANSITION_FORMAT,
+    ENTRANCE_TRANSITION_SIZE,
+    LOCATION_KIND_CHEST,
+    LOCATION_KIND_LOCATION_FLAG,
+    LOCATION_KIND_SCENEFLAG,
+    RANDOMIZED_SCENEFLAG_SIZE,
+    SEQUENCE_OFFSET,
+    TRANSITION_KIND_ENTRANCE,
+    TRANSITION_KIND_FI_WARP,
+    TRACKER_STATE_FORMAT,
+    TRACKER_STATE_SIZE,
+    LocationLogGameMock,
+    LocationLogReader,
+    TrackerError,
+    empty_tracker_state,
+    event_location_id,
+    location_id,
+    pack_randomized_sceneflags,
+    read_tracker_state,
+    unpack_entrance_history,
+    unpack_location_flags,
+)
+
+
+def test_location_id_encoding() -> None:
+    assert location_id(LOCATION_KIND_CHEST, 11, 5) == 0x020B0005
+    assert location_id(LOCATION_KIND_SCENEFLAG, 0, 0x7F) == 0x0000007F
+    assert location_id(LOCATION_KIND_LOCATION_FLAG, 0, 0xABC) == 0x04000ABC
+
+
+def test_event_location_id_includes_event_file() -> None:
+    assert event_location_id("105-Terry", 12) == 0x0306900C
+    assert event_location_id("105-Terry", 12) != event_location_id("201-ForestD3", 12)
+
+
+def test_randomized_sceneflags_are_packed() -> None:
+    packed = pack_randomized_sceneflags([("F000", 5), ("D100", 126)])
+
+    assert len(packed) == MAX_RANDOMIZED_SCENEFLAGS * RANDOMIZED_SCENEFLAG_SIZE
+    assert packed[:RANDOMIZED_SCENEFLAG_SIZE] == b"F000\0\0\0\0\x05\0"
+    assert packed[RANDOMIZED_SCENEFLAG_SIZE + 8] == 126
+    # The entry after the last one must be empty to end the table
+    assert not any(packed[2 * RANDOMIZED_SCENEFLAG_SIZE :])
+
+
+def test_bad_randomized_sceneflags_are_rejected() -> None:
+    for sceneflags in (
+        [("F000", 0xFF)],
+        [("TooLongName", 5)],
+        [("F000", 1)] * MAX_RANDOMIZED_SCENEFLAGS,
+    ):
+        try:
+            pack_randomized_sceneflags(sceneflags)
+        except TrackerError:
+            continue
+
+        assert False, f"{sceneflags[:1]} should have been rejected"
+
+
+def test_records_are_read_once() -> None:
+    game = LocationLogGameMock()
+    reader = LocationLogReader(game.read)
+
+    game.frame = 10
+    game.log_location_check(location_id(LOCATION_KIND_CHEST, 11, 5), 28)
+    game.frame = 12
+    game.log_location_check(location_id(LOCATION_KIND_SCENEFLAG, 11, 50), 9)
+
+    records = reader.update()
+    assert [(r.itemid, r.frame) for r in records] == [(28, 10), (9, 12)]
+    assert records[0].location_id == 0x020B0005
+    assert reader.update() == []
+    assert not reader.lost_records
+
+
+def test_overflow_is_reported() -> None:
+    game = LocationLogGameMock()
+    reader = LocationLogReader(game.read)
+
+    for itemid in range(LOCATION_LOG_CAPACITY + 3):
+        game.log_location_check(itemid, itemid)
+
+    records = reader.update()
+    assert reader.lost_records
+    assert len(records) == LOCATION_LOG_CAPACITY
+    assert records[0].itemid == 3
+
+
+def test_bad_magic_is_ignored() -> None:
+    game = LocationLogGameMock()
+    game.memory[0:4] = b"\0\0\0\0"
+
+    assert LocationLogReader(game.read).update() is None
+
+
+def make_tracker_state(sequence: int) -> bytearray:
+    memory = bytearray(empty_tracker_state())
+    struct.pack_into(
+        TRACKER_STATE_FORMAT,
+        memory,
+        0,
+        *struct.unpack_from("<4sHH", memory),
+        sequence,
+        b"F000\0\0\0\0",
+        1,  # room
+        2,  # layer
+        3,  # entrance
+        0,  # night
+        2,  # sword
+        True,  # in_game
+        24,  # health
+        24,  # health_capacity
+        1000,  # stamina
+        -1,  # minigame
+        *range(8),
+        bytes([0x82] + [0] * 25),
+        bytes([2] + [0] * 25),
+    )
+    return memory
+
+
+def test_tracker_state_is_read() -> None:
+    memory = make_tracker_state(sequence=4)
+    state = read_tracker_state(lambda offset, size: memory[offset : offset + size])
+
+    assert len(memory) == TRACKER_STATE_SIZE == 0x80
+    assert state is not None
+    assert state.stage_name == "F000"
+    assert (state.room, state.layer, state.entrance) == (1, 2, 3)
+    assert not state.night
+    assert state.in_game
+    assert state.minigame == -1
+    assert state.pouch_items == tuple(range(8))
+    assert state.dungeon_item_flags[0] == 0x82
+    assert state.small_keys[0] == 2
+
+
+def test_tracker_state_torn_reads_are_retried() -> None:
+    memory = make_tracker_state(sequence=5)
+    reads = 0
+
+    def read(offset: int, size: int) -> bytes:
+        nonlocal reads
+        reads += 1
+        # The game finishes its update after the first read
+        if reads > 1:
+            struct.pack_into("<I", memory, SEQUENCE_OFFSET, 6)
+        return bytes(memory[offset : offset + size])
+
+    state = read_tracker_state(read)
+    assert state is not None
+    assert state.stage_name == "F000"
+
+
+def test_tracker_state_bad_magic_is_ignored() -> None:
+    memory = make_tracker_state(sequence=0)
+    memory[0:4] = b"\0\0\0\0"
+
+    state = read_tracker_state(lambda offset, size: memory[offset : offset + size])
+    assert state is None
+
+
+def make_entrance_history(count: int) -> bytearray:
+    memory = bytearray(ENTRANCE_HISTORY_SIZE)
+    struct.pack_into("<I", memory, 0, count)
+
+    # Mirrors record_entrance_transition() in entrance.rs
+    for index in range(count):
+        struct.pack_into(
+            ENTRANCE_TRANSITION_FORMAT,
+            memory,
+            ENTRANCE_HISTORY_HEADER_SIZE
+            + (index % ENTRANCE_HISTORY_CAPACITY) * ENTRANCE_TRANSITION_SIZE,
+            b"F000",
+            0,
+            0,
+            index & 0xFF,
+            TRANSITION_KIND_FI_WARP if index == 0 else TRANSITION_KIND_ENTRANCE,
+            b"F001r",
+            1,
+            2,
+            index & 0xFF,
+            0,
+        )
+
+    return memory
+
+
+def test_entrance_history() -> None:
+    assert ENTRANCE_HISTORY_SIZE == 772
+
+    transitions = unpack_entrance_history(make_entrance_history(3))
+    assert len(transitions) == 3
+    assert transitions[0].kind == TRANSITION_KIND_FI_WARP
+    assert transitions[2].from_stage_name == "F000"
+    assert transitions[2].from_arrival_entrance == 2
+    assert transitions[2].to_stage_name == "F001r"
+    assert (transitions[2].to_room, transitions[2].to_layer) == (1, 2)
+    assert not transitions[2].to_night
+
+
+def test_entrance_history_wraps_around() -> None:
+    count = ENTRANCE_HISTORY_CAPACITY + 5
+    transitions = unpack_entrance_history(make_entrance_history(count))
+
+    # Only the newest transitions are left, oldest first
+    assert len(transitions) == ENTRANCE_HISTORY_CAPACITY
+    assert transitions[0].to_entrance == 5
+    assert transitions[-1].to_entrance == count - 1
+
+
+def test_location_flags() -> None:
+    # The location flags follow the entrance history in RandoSaveData
+    assert (
+        SAVEFILE_LOCATION_FLAGS_OFFSET
+        == SAVEFILE_ENTRANCE_HISTORY_OFFSET + ENTRANCE_HISTORY_SIZE
+    )
+
+    data = bytearray(512)
+    data[0] = 0b1000_0001
+    struct.pack_into("<H", data, 0xFF * 2, 0x8000)
+
+    assert unpack_location_flags(bytes(data)) == {0, 7, 4095}
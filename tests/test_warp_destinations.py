@@ -0,0 +1,92 @@
+import os
+import struct
+import sys
+
+import yaml
+
+ROOT_PATH = os.path.abspath(os.path.join(os.path.dirname(__file__), ".."))
+sys.path.insert(0, ROOT_PATH)
+
+from constants.asmconstants import MAX_WARP_DESTINATIONS, SCENE_NAME_TO_SCENE_INDEX
+from util.warp_destinations import (
+    FLAG_TYPE_SCENEFLAG,
+    FLAG_TYPE_STORYFLAG,
+    WARP_DESTINATION_FORMAT,
+    WARP_DESTINATION_SIZE,
+    get_warp_destinations,
+    pack_warp_destinations,
+)
+
+
+def load_yaml(*path: str):
+    with open(os.path.join(ROOT_PATH, *path), encoding="utf-8") as file:
+        return yaml.safe_load(file)
+
+
+SPAWN_INFOS = {
+    entrance["forward"]["connection"]: entrance["forward"]["spawn_info"][0]
+    for entrance in load_yaml("data", "entrance_shuffle_data.yaml")
+    if isinstance(entrance["forward"]["spawn_info"], list)
+}
+DESTINATIONS = get_warp_destinations(
+    load_yaml("data", "warp_destinations.yaml"),
+    load_yaml("data", "bird_statue_data.yaml"),
+    SPAWN_INFOS.__getitem__,
+)
+
+
+def test_warp_destinations() -> None:
+    # The last destination must be left empty to end the table
+    assert len(DESTINATIONS) < MAX_WARP_DESTINATIONS
+
+    # Menu entries need unique names so they don't clash in the event file
+    names = [destination.name for destination in DESTINATIONS]
+    assert len(names) == len(set(names))
+
+    # Destinations unlocked on arrival can't share a flag
+    unlock_flags = [
+        (destination.flag_type, destination.sceneindex, destination.flag)
+        for destination in DESTINATIONS
+        if destination.unlock_on_arrival
+    ]
+    assert len(unlock_flags) == len(set(unlock_flags))
+
+    destinations = {destination.name: destination for destination in DESTINATIONS}
+    assert destinations["Faron Woods Entry Statue"].flag_type == FLAG_TYPE_STORYFLAG
+    assert destinations["Faron Woods Entry Statue"].flag == 800
+    assert destinations["Skyview Temple"].spawn_info["stage"] == "D100"
+    assert destinations["Skyview Temple"].flag_type == FLAG_TYPE_SCENEFLAG
+    assert destinations["Skyview Temple"].sceneindex == SCENE_NAME_TO_SCENE_INDEX[
+        "-Unused-"
+    ]
+
+
+def test_warp_destination_packing() -> None:
+    packed_destinations = pack_warp_destinations(DESTINATIONS)
+
+    assert WARP_DESTINATION_SIZE == 20
+    assert len(packed_destinations) == MAX_WARP_DESTINATIONS * WARP_DESTINATION_SIZE
+
+    deep_woods_index = [destination.name for destination in DESTINATIONS].index(
+        "Deep Woods Statue"
+    )
+    assert struct.unpack_from(
+        WARP_DESTINATION_FORMAT,
+        packed_destinations,
+        deep_woods_index * WARP_DESTINATION_SIZE,
+    ) == (
+        b"F101\0\0\0\0",
+        SPAWN_INFOS["Faron Pillar -> Deep Woods Statue"]["room"],
+        SPAWN_INFOS["Faron Pillar -> Deep Woods Statue"]["layer"],
+        SPAWN_INFOS["Faron Pillar -> Deep Woods Statue"]["entrance"],
+        0,  # day
+        FLAG_TYPE_SCENEFLAG,
+        1,
+        SCENE_NAME_TO_SCENE_INDEX["Faron Woods"],
+        1,
+        103,
+        0,  # statues are unlocked by the game
+    )
+
+    # Table ends with an empty destination
+    assert packed_destinations[len(DESTINATIONS) * WARP_DESTINATION_SIZE] == 0
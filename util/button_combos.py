@@ -0,0 +1,51 @@
+"""
+Packs the button combos in RandomizerSettings (see
+asm/additions/rust-additions/src/settings.rs) from the combo settings.
+"""
+
+import struct
+
+# Matches BUTTON_INPUTS in input.rs
+BUTTON_INPUTS = {
+    "dpad_left": 0x1,
+    "dpad_right": 0x2,
+    "dpad_down": 0x4,
+    "dpad_up": 0x8,
+    "plus": 0x10,
+    "y": 0x100,
+    "x": 0x200,
+    "b": 0x400,
+    "a": 0x800,
+    "minus": 0x1000,
+    "zl": 0x2000,
+    "l": 0x4000,
+    "left_stick": 0x10000,
+    "right_stick": 0x20000,
+    "r": 0x40000,
+    "zr": 0x80000,
+}
+
+# Options of the soft_reset_combo and back_in_time_combo settings
+BUTTON_COMBO_OPTIONS = {
+    "disabled": (),
+    "l_stick_a_r": ("left_stick", "a", "r"),
+    "l_r_minus": ("l", "r", "minus"),
+    "zl_zr_minus": ("zl", "zr", "minus"),
+    "l_r_dpad_down": ("l", "r", "dpad_down"),
+    "l": ("l",),
+    "zl": ("zl",),
+    "r": ("r",),
+    "zr": ("zr",),
+}
+
+# buttons, hold_frames, enabled
+BUTTON_COMBO_FORMAT = "<IHBx"
+BUTTON_COMBO_SIZE = struct.calcsize(BUTTON_COMBO_FORMAT)
+
+
+def pack_button_combo(option: str, hold_frames: int) -> bytes:
+    buttons = 0
+    for button in BUTTON_COMBO_OPTIONS[option]:
+        buttons |= BUTTON_INPUTS[button]
+
+    return struct.pack(BUTTON_COMBO_FORMAT, buttons, hold_frames, int(buttons != 0))
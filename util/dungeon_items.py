@@ -0,0 +1,104 @@
+"""
+Helpers for data/dungeon_items.yaml.
+
+The table is packed for DUNGEON_ITEMS in asm/additions/rust-additions/src/item.rs,
+which sets the flag or adds to the counter of a dungeon item when it's
+collected.
+"""
+
+import struct
+
+from constants.asmconstants import MAX_DUNGEON_ITEMS
+from util.flags import FLAG_SPACE_DUNGEONFLAG, pack_flag_ref, parse_flag_ref
+
+# Matches DUNGEONFLAG_SMALL_KEY_COUNTER in flag.rs
+DUNGEONFLAG_SMALL_KEY_COUNTER = 1
+
+# Whether each kind of dungeon item adds to a counter. Rewards and completion
+# markers can be given either way
+DUNGEON_ITEM_KINDS = {
+    "boss_key": False,
+    "map": False,
+    "compass": False,
+    "small_key": True,
+    "key_ring": True,
+    "dungeon_reward": None,
+    "dungeon_completed": None,
+}
+
+# Kinds the game itself reads from the dungeonflags
+DUNGEONFLAG_KINDS = ("boss_key", "map", "compass", "small_key", "key_ring")
+
+# itemid, amount, padding, flag (FlagRef)
+DUNGEON_ITEM_FORMAT = "<HBx4s"
+DUNGEON_ITEM_SIZE = struct.calcsize(DUNGEON_ITEM_FORMAT)
+
+
+class DungeonItemError(RuntimeError):
+    pass
+
+
+def pack_dungeon_item(entry: dict) -> bytes:
+    itemid = entry["id"]
+    kind = entry.get("kind")
+
+    if kind not in DUNGEON_ITEM_KINDS:
+        raise DungeonItemError(f"Unknown kind for dungeon item {itemid}: {kind}")
+
+    if ("flag" in entry) == ("counter" in entry):
+        raise DungeonItemError(
+            f"Dungeon item {itemid} needs either a flag or a counter"
+        )
+
+    is_counter = "counter" in entry
+    if DUNGEON_ITEM_KINDS[kind] not in (None, is_counter):
+        needs = "a counter" if DUNGEON_ITEM_KINDS[kind] else "a flag"
+        raise DungeonItemError(f"Dungeon item {itemid} is a {kind}, it needs {needs}")
+
+    space, sceneindex, flag = parse_flag_ref(
+        entry["counter"] if is_counter else entry["flag"]
+    )
+    if kind in DUNGEONFLAG_KINDS and space != FLAG_SPACE_DUNGEONFLAG:
+        raise DungeonItemError(
+            f"Dungeon item {itemid} is a {kind}, it needs a dungeonflag"
+        )
+
+    if (
+        is_counter
+        and kind in DUNGEONFLAG_KINDS
+        and flag != DUNGEONFLAG_SMALL_KEY_COUNTER
+    ):
+        raise DungeonItemError(
+            f"Dungeon item {itemid} is a {kind}, it needs the small key counter ({DUNGEONFLAG_SMALL_KEY_COUNTER})"
+        )
+
+    amount = entry.get("amount", 1) if is_counter else 0
+    if is_counter and not 0 < amount < 0x100:
+        raise DungeonItemError(f"Dungeon item {itemid} has a bad amount: {amount}")
+
+    return struct.pack(
+        DUNGEON_ITEM_FORMAT, itemid, amount, pack_flag_ref(space, flag, sceneindex)
+    )
+
+
+def pack_dungeon_items(entries: list[dict]) -> bytes:
+    # The last entry is left empty to end the table
+    if len(entries) >= MAX_DUNGEON_ITEMS:
+        raise DungeonItemError(
+            f"Too many dungeon items ({len(entries)}), the maximum is {MAX_DUNGEON_ITEMS - 1}"
+        )
+
+    packed_entries = b""
+    seen_itemids = set()
+    for entry in entries:
+        itemid = entry["id"]
+        if not 0 < itemid <= 0xFFFF:
+            raise DungeonItemError(f"Bad dungeon item id: {itemid}")
+        if itemid in seen_itemids:
+            raise DungeonItemError(f"Dungeon item {itemid} is listed more than once")
+        seen_itemids.add(itemid)
+
+        packed_entries += pack_dungeon_item(entry)
+
+    # Pad out the whole table so stale data can't follow the last entry
+    return packed_entries.ljust(MAX_DUNGEON_ITEMS * DUNGEON_ITEM_SIZE, b"\0")
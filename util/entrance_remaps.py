@@ -0,0 +1,72 @@
+"""
+Packs runtime entrance remaps for apply_entrance_remaps in
+asm/additions/rust-additions/src/entrance.rs.
+
+A remap looks like:
+    {
+        "from": {"stage": "F000", "room": 0, "layer": 0, "entrance": 3},
+        "to": {"stage": "F001r", "room": 1, "layer": 0, "entrance": 5},
+        "night": True,  # optional
+        "trial": False,  # optional
+    }
+The room and layer in "from" and the layer in "to" are optional.
+"""
+
+import struct
+
+from constants.asmconstants import MAX_ENTRANCE_REMAPS
+
+# from_stage_name, from_room, from_layer, from_entrance, forced_night,
+# to_stage_name, to_room, to_layer, to_entrance, forced_trial
+ENTRANCE_REMAP_FORMAT = "<8sBBBB8sBBBB"
+ENTRANCE_REMAP_SIZE = struct.calcsize(ENTRANCE_REMAP_FORMAT)
+
+
+class EntranceRemapError(RuntimeError):
+    pass
+
+
+def optional_state(remap: dict, field: str) -> int:
+    if field not in remap:
+        return 0xFF
+    return 1 if remap[field] else 0
+
+
+def pack_entrance_remaps(remaps: list[dict]) -> bytes:
+    # The last remap is left empty to end the table
+    if len(remaps) >= MAX_ENTRANCE_REMAPS:
+        raise EntranceRemapError(
+            f"Too many entrance remaps ({len(remaps)}), the maximum is {MAX_ENTRANCE_REMAPS - 1}"
+        )
+
+    packed_remaps = b""
+    for remap in remaps:
+        from_info = remap["from"]
+        to_info = remap["to"]
+
+        for info in (from_info, to_info):
+            for field in ["stage", "entrance"]:
+                if field not in info:
+                    raise EntranceRemapError(
+                        f"Entrance remap {remap} is missing the {field} field"
+                    )
+
+            if len(info["stage"]) >= 8:
+                raise EntranceRemapError(f'Stage name "{info["stage"]}" is too long')
+
+        packed_remaps += struct.pack(
+            ENTRANCE_REMAP_FORMAT,
+            from_info["stage"].encode("ascii"),
+            from_info.get("room", 0xFF),
+            from_info.get("layer", 0xFF),
+            from_info["entrance"],
+            optional_state(remap, "night"),
+            to_info["stage"].encode("ascii"),
+            to_info.get("room", 0),
+            to_info.get("layer", 0xFF),
+            to_info["entrance"],
+            optional_state(remap, "trial"),
+        )
+
+    # Pad out the whole table so stale data can't follow the last remap
+    return packed_remaps.ljust(MAX_ENTRANCE_REMAPS * ENTRANCE_REMAP_SIZE, b"\0")
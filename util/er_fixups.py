@@ -0,0 +1,85 @@
+"""
+Packer for data/patches/erfixups.yaml.
+
+The packed rules are read by handle_er_cases in
+asm/additions/rust-additions/src/entrance.rs.
+"""
+
+import struct
+
+from constants.asmconstants import (
+    ER_FIXUP_RULE_SIZE,
+    MAX_ER_FIXUP_FLAG_RANGES,
+    MAX_ER_FIXUP_RULES,
+    SCENE_NAME_TO_SCENE_INDEX,
+)
+
+# flag_type, value, sceneindex, count, start
+ER_FIXUP_FLAG_RANGE_FORMAT = "<BBBBH"
+ER_FIXUP_FLAG_RANGE_SIZE = struct.calcsize(ER_FIXUP_FLAG_RANGE_FORMAT)
+# stage, entrance, new_entrance
+ER_FIXUP_RULE_HEADER_FORMAT = "<8sBB2x"
+
+
+def pack_flag_ranges(flag_refs: list[dict]) -> bytes:
+    flag_ranges = b""
+
+    for flag_ref in flag_refs:
+        value = 1 if flag_ref["value"] else 0
+
+        if "storyflag" in flag_ref:
+            flag_type = 1
+            sceneindex = 0
+            flags = flag_ref["storyflag"]
+        else:
+            flag_type = 2
+            sceneindex = SCENE_NAME_TO_SCENE_INDEX[flag_ref["sceneflag"]["scene"]]
+            flags = flag_ref["sceneflag"]["flags"]
+
+        # Consecutive flags are merged into a single range
+        flags = sorted(flags)
+        start = flags[0]
+        for previous, flag in zip(flags, flags[1:] + [None]):
+            if flag == previous + 1 and flag - start < 0xFF:
+                continue
+
+            flag_ranges += struct.pack(
+                ER_FIXUP_FLAG_RANGE_FORMAT,
+                flag_type,
+                value,
+                sceneindex,
+                previous - start + 1,
+                start,
+            )
+            start = flag
+
+    # If this fails, the rust struct will need more ranges
+    assert len(flag_ranges) <= MAX_ER_FIXUP_FLAG_RANGES * ER_FIXUP_FLAG_RANGE_SIZE
+
+    return flag_ranges.ljust(MAX_ER_FIXUP_FLAG_RANGES * ER_FIXUP_FLAG_RANGE_SIZE, b"\0")
+
+
+def pack_er_fixup_rules(er_fixups: list[dict]) -> bytes:
+    packed_rules = b""
+
+    for rule in er_fixups:
+        conditions = pack_flag_ranges(rule.get("conditions", []))
+        effects = pack_flag_ranges(rule.get("effects", []))
+        new_entrance = rule.get("new_entrance", 0xFF)
+
+        for stage_name, entrance in rule["next_stage"]:
+            packed_rules += struct.pack(
+                ER_FIXUP_RULE_HEADER_FORMAT,
+                stage_name.encode("ascii"),
+                entrance,
+                new_entrance,
+            )
+            packed_rules += conditions
+            packed_rules += effects
+
+    # If this fails, the rust array size will need increasing (the last
+    # rule is left empty to end the table)
+    assert len(packed_rules) < MAX_ER_FIXUP_RULES * ER_FIXUP_RULE_SIZE
+
+    # Pad out the whole table so stale data can't follow the last rule
+    return packed_rules.ljust(MAX_ER_FIXUP_RULES * ER_FIXUP_RULE_SIZE, b"\0")
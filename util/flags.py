@@ -0,0 +1,77 @@
+"""
+Packs flag references for FlagRef in asm/additions/rust-additions/src/flag.rs
+so data tables can point at any kind of flag.
+"""
+
+import struct
+
+from constants.asmconstants import SCENE_NAME_TO_SCENE_INDEX
+
+# Matches FLAG_SPACE in flag.rs
+FLAG_SPACE_NONE = 0
+FLAG_SPACE_STORYFLAG = 1
+FLAG_SPACE_SCENEFLAG = 2
+FLAG_SPACE_ITEMFLAG = 3
+FLAG_SPACE_DUNGEONFLAG = 4
+FLAG_SPACE_TEMPFLAG = 5
+FLAG_SPACE_ZONEFLAG = 6
+FLAG_SPACE_STAGE_OBJECT_FLAG = 7
+FLAG_SPACE_LOCATION_FLAG = 8
+
+FLAG_SPACES = {
+    "storyflag": FLAG_SPACE_STORYFLAG,
+    "sceneflag": FLAG_SPACE_SCENEFLAG,
+    "itemflag": FLAG_SPACE_ITEMFLAG,
+    "dungeonflag": FLAG_SPACE_DUNGEONFLAG,
+    "tempflag": FLAG_SPACE_TEMPFLAG,
+    "zoneflag": FLAG_SPACE_ZONEFLAG,
+    "stageobjectflag": FLAG_SPACE_STAGE_OBJECT_FLAG,
+    "locationflag": FLAG_SPACE_LOCATION_FLAG,
+}
+
+# Flag spaces where the scene is part of the flag
+SCENE_FLAG_SPACES = (
+    FLAG_SPACE_SCENEFLAG,
+    FLAG_SPACE_DUNGEONFLAG,
+    FLAG_SPACE_STAGE_OBJECT_FLAG,
+)
+
+# space, sceneindex (roomid for zoneflags), flag
+FLAG_REF_FORMAT = "<BBH"
+FLAG_REF_SIZE = struct.calcsize(FLAG_REF_FORMAT)
+
+
+class FlagRefError(RuntimeError):
+    pass
+
+
+def pack_flag_ref(space: int, flag: int, sceneindex: int = 0) -> bytes:
+    return struct.pack(FLAG_REF_FORMAT, space, sceneindex, flag)
+
+
+def parse_flag_ref(flag_ref: dict | None) -> tuple[int, int, int]:
+    """
+    Turns a flag from a data file into (space, sceneindex, flag), e.g.
+    `{"storyflag": 22}` or `{"sceneflag": {"scene": "Skyloft", "flag": 29}}`.
+    Zoneflags give a room instead, e.g. `{"zoneflag": {"room": 1, "flag": 3}}`.
+    Stage object flags use the object id as the flag.
+    """
+    if flag_ref is None:
+        return FLAG_SPACE_NONE, 0, 0
+
+    if len(flag_ref) != 1 or next(iter(flag_ref)) not in FLAG_SPACES:
+        raise FlagRefError(f"Unknown flag {flag_ref}")
+
+    space_name, value = next(iter(flag_ref.items()))
+    space = FLAG_SPACES[space_name]
+
+    if space == FLAG_SPACE_ZONEFLAG:
+        return space, value["room"], value["flag"]
+
+    if space not in SCENE_FLAG_SPACES:
+        return space, 0, value
+
+    scene = value["scene"]
+    if scene not in SCENE_NAME_TO_SCENE_INDEX:
+        raise FlagRefError(f'Unknown scene "{scene}" for {space_name} {value["flag"]}')
+    return space, SCENE_NAME_TO_SCENE_INDEX[scene], value["flag"]
@@ -0,0 +1,65 @@
+"""
+Helpers for data/item_display_info.yaml.
+
+The table is packed for ITEM_DISPLAY_INFO in
+asm/additions/rust-additions/src/item.rs, one entry per item id, so item
+offsets and scales can be changed without rebuilding the rust additions.
+"""
+
+import struct
+
+from constants.asmconstants import CUSTOM_ITEMIDS, ITEM_DISPLAY_INFO_COUNT
+
+# y_offset, wall_offset, model_scale, freestanding_scale, wall_rotation (x, y,
+# z), flags
+ITEM_DISPLAY_INFO_FORMAT = "<ffffHHHBx"
+ITEM_DISPLAY_INFO_SIZE = struct.calcsize(ITEM_DISPLAY_INFO_FORMAT)
+
+# Matches ITEM_DISPLAY_FLAG_DEFAULT_SCALING in item.rs
+ITEM_DISPLAY_FLAG_DEFAULT_SCALING = 1 << 0
+
+
+class ItemDisplayInfoError(RuntimeError):
+    pass
+
+
+def pack_item_display_info_entry(entry: dict) -> bytes:
+    flags = 0
+    if entry.get("default_scaling", False):
+        flags |= ITEM_DISPLAY_FLAG_DEFAULT_SCALING
+
+    return struct.pack(
+        ITEM_DISPLAY_INFO_FORMAT,
+        entry.get("y_offset", 0.0),
+        entry.get("wall_offset", 0.0),
+        entry.get("model_scale", 1.0),
+        entry.get("freestanding_scale", 1.0),
+        *entry.get("wall_rotation", (0, 0, 0)),
+        flags,
+    )
+
+
+def pack_item_display_info(entries: list[dict]) -> bytes:
+    # Every item starts with the defaults, so the whole table is always written
+    table = [pack_item_display_info_entry({})] * ITEM_DISPLAY_INFO_COUNT
+    listed_ids = set()
+
+    for entry in entries:
+        itemid = entry["id"]
+        if not 0 <= itemid < ITEM_DISPLAY_INFO_COUNT:
+            raise ItemDisplayInfoError(
+                f"Item id out of range for display info: {itemid}"
+            )
+        if itemid in CUSTOM_ITEMIDS:
+            raise ItemDisplayInfoError(
+                f"Custom items keep their display info in customitem.rs: {itemid}"
+            )
+        if itemid in listed_ids:
+            raise ItemDisplayInfoError(f"Item listed twice in display info: {itemid}")
+        if len(entry.get("wall_rotation", (0, 0, 0))) != 3:
+            raise ItemDisplayInfoError(f"Wall rotation needs x, y and z: {itemid}")
+
+        listed_ids.add(itemid)
+        table[itemid] = pack_item_display_info_entry(entry)
+
+    return b"".join(table)
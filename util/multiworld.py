@@ -0,0 +1,187 @@
+"""
+Host-side mock of the multiworld item mailbox.
+
+The game side lives in asm/additions/rust-additions/src/multiworld.rs. Both
+ends of the protocol are mirrored here so the client logic can be tested
+without a console or an emulator.
+"""
+
+import struct
+from typing import Callable
+
+from constants.asmconstants import (
+    MULTIWORLD_MAILBOX_CAPACITY,
+    MULTIWORLD_MAILBOX_MAGIC,
+    MULTIWORLD_MAILBOX_STATUS_INVALID_ITEM,
+    MULTIWORLD_MAILBOX_STATUS_NO_SAVE_DATA,
+    MULTIWORLD_MAILBOX_STATUS_OK,
+    MULTIWORLD_MAILBOX_VERSION,
+)
+
+# magic, version, capacity, write_index, read_index, status, rejected_index
+MAILBOX_HEADER_FORMAT = "<4sHHIIH2xI"
+# index, itemid, sender
+MAILBOX_ENTRY_FORMAT = "<IHH"
+
+MAILBOX_HEADER_SIZE = struct.calcsize(MAILBOX_HEADER_FORMAT)
+MAILBOX_ENTRY_SIZE = struct.calcsize(MAILBOX_ENTRY_FORMAT)
+MAILBOX_SIZE = MAILBOX_HEADER_SIZE + MAILBOX_ENTRY_SIZE * MULTIWORLD_MAILBOX_CAPACITY
+
+WRITE_INDEX_OFFSET = 8
+READ_INDEX_OFFSET = 12
+STATUS_OFFSET = 16
+REJECTED_INDEX_OFFSET = 20
+
+
+def empty_mailbox() -> bytes:
+    header = struct.pack(
+        MAILBOX_HEADER_FORMAT,
+        MULTIWORLD_MAILBOX_MAGIC,
+        MULTIWORLD_MAILBOX_VERSION,
+        MULTIWORLD_MAILBOX_CAPACITY,
+        0,
+        0,
+        MULTIWORLD_MAILBOX_STATUS_OK,
+        0,
+    )
+    return header + bytes(MAILBOX_ENTRY_SIZE * MULTIWORLD_MAILBOX_CAPACITY)
+
+
+def entry_offset(index: int) -> int:
+    slot = index % MULTIWORLD_MAILBOX_CAPACITY
+    return MAILBOX_HEADER_SIZE + slot * MAILBOX_ENTRY_SIZE
+
+
+class MailboxClient:
+    """
+    Pushes received items into the mailbox. `read` and `write` take offsets
+    relative to the start of the mailbox so the same client can be pointed at
+    emulator memory or at a MailboxGameMock.
+    """
+
+    def __init__(
+        self,
+        read: Callable[[int, int], bytes],
+        write: Callable[[int, bytes], None],
+    ) -> None:
+        self.read = read
+        self.write = write
+        self.items: list[tuple[int, int]] = []
+        self.write_index = 0
+        self.status = MULTIWORLD_MAILBOX_STATUS_OK
+        self.rejected_index = 0
+
+    def queue_item(self, itemid: int, sender: int = 0) -> None:
+        self.items.append((itemid, sender))
+
+    def update(self) -> bool:
+        magic, version, capacity, _, read_index, status, rejected_index = (
+            struct.unpack(MAILBOX_HEADER_FORMAT, self.read(0, MAILBOX_HEADER_SIZE))
+        )
+
+        if (
+            magic != MULTIWORLD_MAILBOX_MAGIC
+            or version != MULTIWORLD_MAILBOX_VERSION
+            or capacity != MULTIWORLD_MAILBOX_CAPACITY
+        ):
+            return False
+
+        # Left for the caller to report, the game won't read past a rejected
+        # item until it's replaced with replace_item
+        self.status = status
+        self.rejected_index = rejected_index
+
+        # The player loaded an older save, resend everything after it
+        if read_index < self.write_index:
+            self.write_index = read_index
+
+        limit = min(len(self.items), read_index + capacity)
+
+        while self.write_index < limit:
+            itemid, sender = self.items[self.write_index]
+            self.write(
+                entry_offset(self.write_index),
+                struct.pack(MAILBOX_ENTRY_FORMAT, self.write_index, itemid, sender),
+            )
+            self.write_index += 1
+
+        # Only publish the new items once their entries have been written
+        self.write(WRITE_INDEX_OFFSET, struct.pack("<I", self.write_index))
+
+        return True
+
+    def replace_item(self, index: int, itemid: int, sender: int = 0) -> None:
+        self.items[index] = (itemid, sender)
+        if index < self.write_index:
+            self.write(
+                entry_offset(index),
+                struct.pack(MAILBOX_ENTRY_FORMAT, index, itemid, sender),
+            )
+
+
+class MailboxGameMock:
+    """Mirrors process_multiworld_mailbox() from multiworld.rs."""
+
+    def __init__(self) -> None:
+        self.memory = bytearray(empty_mailbox())
+        # SaveFile.rando.multiworld_items_received
+        self.items_received = 0
+        self.given_items: list[int] = []
+        # Whether get_rando_save_data() finds the randomizer's space free
+        self.has_save_data = True
+
+    def read(self, offset: int, size: int) -> bytes:
+        return bytes(self.memory[offset : offset + size])
+
+    def write(self, offset: int, data: bytes) -> None:
+        self.memory[offset : offset + len(data)] = data
+
+    def frame(self, can_receive_items: bool = True) -> None:
+        magic, version, _, write_index, _, _, _ = struct.unpack_from(
+            MAILBOX_HEADER_FORMAT, self.memory
+        )
+
+        if (
+            magic != MULTIWORLD_MAILBOX_MAGIC
+            or version != MULTIWORLD_MAILBOX_VERSION
+        ):
+            return
+
+        if not self.has_save_data:
+            self.set_status(MULTIWORLD_MAILBOX_STATUS_NO_SAVE_DATA)
+            return
+
+        received = self.items_received
+        struct.pack_into("<I", self.memory, READ_INDEX_OFFSET, received)
+
+        if write_index <= received:
+            self.set_status(MULTIWORLD_MAILBOX_STATUS_OK)
+            return
+
+        index, itemid, _ = struct.unpack_from(
+            MAILBOX_ENTRY_FORMAT, self.memory, entry_offset(received)
+        )
+        if index != received:
+            return
+
+        if itemid > 0xFF:
+            self.set_status(MULTIWORLD_MAILBOX_STATUS_INVALID_ITEM)
+            struct.pack_into("<I", self.memory, REJECTED_INDEX_OFFSET, received)
+            return
+
+        self.set_status(MULTIWORLD_MAILBOX_STATUS_OK)
+
+        if not can_receive_items:
+            return
+
+        self.given_items.append(itemid)
+
+        self.items_received = received + 1
+        struct.pack_into("<I", self.memory, READ_INDEX_OFFSET, received + 1)
+
+    def set_status(self, status: int) -> None:
+        struct.pack_into("<H", self.memory, STATUS_OFFSET, status)
+
+    def reload(self, items_received: int) -> None:
+        self.items_received = items_received
+        del self.given_items[items_received:]
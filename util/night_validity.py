@@ -0,0 +1,83 @@
+"""
+Helpers for data/night_validity.yaml.
+
+The same table is packed for next_stage_is_valid_at_night in
+asm/additions/rust-additions/src/entrance.rs and used by the logic to find the
+day only areas, so both sides agree on which stages can be entered at night.
+"""
+
+import struct
+
+from constants.asmconstants import (
+    MAX_NIGHT_VALIDITY_ENTRANCES,
+    MAX_NIGHT_VALIDITY_RULES,
+)
+
+# stage_pattern, allow, entrances
+NIGHT_VALIDITY_RULE_FORMAT = f"<8sB{MAX_NIGHT_VALIDITY_ENTRANCES}s"
+NIGHT_VALIDITY_RULE_SIZE = struct.calcsize(NIGHT_VALIDITY_RULE_FORMAT)
+
+
+def stage_matches(pattern: str, stage: str) -> bool:
+    if pattern.endswith("*"):
+        return stage.startswith(pattern[:-1])
+    return stage == pattern
+
+
+def is_valid_at_night(rules: list[dict], stage: str, entrance: int) -> bool:
+    for rule in rules:
+        if not stage_matches(rule["stage"], stage):
+            continue
+
+        if "entrances" in rule and entrance not in rule["entrances"]:
+            continue
+
+        return rule["allow"]
+
+    return False
+
+
+def get_day_only_areas(rules: list[dict], entrance_data: list[dict]) -> set[str]:
+    # An area is day only if none of the spawns leading into it are valid at night
+    night_validity: dict[str, bool] = {}
+
+    # Areas without spawns of their own (e.g. the open sky) are named by a rule
+    for rule in rules:
+        for area in rule.get("areas", []):
+            night_validity[area] = night_validity.get(area, False) or rule["allow"]
+
+    for entrance in entrance_data:
+        for direction in ("forward", "return"):
+            if direction not in entrance:
+                continue
+
+            area = entrance[direction]["connection"].split(" -> ")[1]
+            for spawn_info in entrance[direction].get("spawn_info") or []:
+                valid = is_valid_at_night(
+                    rules, spawn_info["stage"], spawn_info["entrance"]
+                )
+                night_validity[area] = night_validity.get(area, False) or valid
+
+    return {area for area, valid in night_validity.items() if not valid}
+
+
+def pack_night_validity_rules(rules: list[dict]) -> bytes:
+    # The last rule is left empty to end the table
+    assert len(rules) < MAX_NIGHT_VALIDITY_RULES
+
+    packed_rules = b""
+    for rule in rules:
+        entrances = rule.get("entrances", [])
+        assert len(entrances) <= MAX_NIGHT_VALIDITY_ENTRANCES
+
+        packed_rules += struct.pack(
+            NIGHT_VALIDITY_RULE_FORMAT,
+            rule["stage"].encode("ascii"),
+            1 if rule["allow"] else 0,
+            bytes(entrances).ljust(MAX_NIGHT_VALIDITY_ENTRANCES, b"\xff"),
+        )
+
+    # Pad out the whole table so stale data can't follow the last rule
+    return packed_rules.ljust(
+        MAX_NIGHT_VALIDITY_RULES * NIGHT_VALIDITY_RULE_SIZE, b"\0"
+    )
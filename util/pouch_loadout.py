@@ -0,0 +1,77 @@
+"""
+Works out where starting pouch items go for the LOADOUT_ITEM startflags read by
+apply_startflag in asm/additions/rust-additions/src/flag.rs.
+
+Items fill the pouch up to its capacity and anything left over is put in Item
+Check storage, the same as when the pouch is full in game.
+"""
+
+from typing import NamedTuple
+
+# Matches LOADOUT_STORAGE in flag.rs
+LOADOUT_STORAGE_POUCH = 0
+LOADOUT_STORAGE_ITEM_CHECK = 1
+
+POUCH_SLOT_COUNT = 8
+ITEM_CHECK_SLOT_COUNT = 60
+
+BASE_POUCH_CAPACITY = 4
+MAX_POUCH_EXPANSIONS = 4
+
+SHIELD_ITEMIDS = range(116, 126)
+
+
+class PouchLoadoutError(RuntimeError):
+    pass
+
+
+class LoadoutItem(NamedTuple):
+    storage: int
+    slot: int
+    itemid: int
+    extra: int
+
+
+def get_pouch_capacity(pouch_count: int) -> int:
+    """
+    The first Progressive Pouch is the Adventure Pouch and each one after it is
+    a Pouch Expansion.
+    """
+    if pouch_count <= 0:
+        return 0
+
+    expansions = min(pouch_count - 1, MAX_POUCH_EXPANSIONS)
+    return BASE_POUCH_CAPACITY + expansions
+
+
+def get_loadout(
+    pouch_items: list[tuple[int, int]], pouch_count: int
+) -> list[LoadoutItem]:
+    """
+    Takes (itemid, extra) pairs and returns the slot each one is stored in.
+    Shields go first, highest itemid first, so the Hylian Shield is always the
+    top slot of the pouch wheel. Everything else keeps its order.
+    """
+    pouch_items = sorted(
+        pouch_items,
+        key=lambda item: -item[0] if item[0] in SHIELD_ITEMIDS else 0,
+    )
+
+    capacity = get_pouch_capacity(pouch_count)
+    loadout = [
+        LoadoutItem(LOADOUT_STORAGE_POUCH, slot, itemid, extra)
+        for slot, (itemid, extra) in enumerate(pouch_items[:capacity])
+    ]
+
+    overflow = pouch_items[capacity:]
+    if len(overflow) > ITEM_CHECK_SLOT_COUNT:
+        raise PouchLoadoutError(
+            f"Too many starting pouch items ({len(pouch_items)}) to fit in the pouch and Item Check"
+        )
+
+    loadout.extend(
+        LoadoutItem(LOADOUT_STORAGE_ITEM_CHECK, slot, itemid, extra)
+        for slot, (itemid, extra) in enumerate(overflow)
+    )
+
+    return loadout
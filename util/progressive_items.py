@@ -0,0 +1,93 @@
+"""
+Helpers for data/progressive_items.yaml.
+
+The item get events for each stage are built from it by
+handle_progressive_items in patches/eventpatchhandler.py and the chains are
+packed for PROGRESSIVE_ITEM_CHAINS in asm/additions/rust-additions/src/item.rs,
+which picks the model of the next stage.
+"""
+
+import struct
+
+from constants.asmconstants import (
+    MAX_PROGRESSIVE_ITEM_CHAINS,
+    MAX_PROGRESSIVE_ITEM_STAGES,
+)
+from util.flags import FLAG_SPACE_ITEMFLAG, pack_flag_ref, parse_flag_ref
+
+PROGRESSIVE_ITEM_NAME_SIZE = 16
+
+# flag (FlagRef), itemid, text, arcname, modelname
+PROGRESSIVE_ITEM_STAGE_FORMAT = (
+    f"<4sHH{PROGRESSIVE_ITEM_NAME_SIZE}s{PROGRESSIVE_ITEM_NAME_SIZE}s"
+)
+PROGRESSIVE_ITEM_STAGE_SIZE = struct.calcsize(PROGRESSIVE_ITEM_STAGE_FORMAT)
+
+# base itemid, stage count, padding
+PROGRESSIVE_ITEM_CHAIN_HEADER_FORMAT = "<HBx"
+PROGRESSIVE_ITEM_CHAIN_SIZE = (
+    struct.calcsize(PROGRESSIVE_ITEM_CHAIN_HEADER_FORMAT)
+    + MAX_PROGRESSIVE_ITEM_STAGES * PROGRESSIVE_ITEM_STAGE_SIZE
+)
+
+
+class ProgressiveItemError(RuntimeError):
+    pass
+
+
+def pack_model_name(name: str | None) -> bytes:
+    if name is None:
+        return b""
+
+    # Leave room for the null terminator
+    if len(name) >= PROGRESSIVE_ITEM_NAME_SIZE:
+        raise ProgressiveItemError(f'Progressive item model name is too long: "{name}"')
+    return name.encode("ascii")
+
+
+def pack_progressive_item_stage(stage: dict) -> bytes:
+    if "flag" in stage:
+        space, sceneindex, flag = parse_flag_ref(stage["flag"])
+    else:
+        space, sceneindex, flag = FLAG_SPACE_ITEMFLAG, 0, stage["itemid"]
+
+    arcname = stage.get("arcname")
+    modelname = stage.get("modelname", arcname)
+
+    return struct.pack(
+        PROGRESSIVE_ITEM_STAGE_FORMAT,
+        pack_flag_ref(space, flag, sceneindex),
+        stage["itemid"],
+        stage["text"],
+        pack_model_name(arcname),
+        pack_model_name(modelname),
+    )
+
+
+def pack_progressive_item_chains(chains: list[dict]) -> bytes:
+    # The last chain is left empty to end the table
+    if len(chains) >= MAX_PROGRESSIVE_ITEM_CHAINS:
+        raise ProgressiveItemError(
+            f"Too many progressive items ({len(chains)}), the maximum is {MAX_PROGRESSIVE_ITEM_CHAINS - 1}"
+        )
+
+    packed_chains = b""
+    for chain in chains:
+        stages = chain["stages"]
+        if not 0 < len(stages) <= MAX_PROGRESSIVE_ITEM_STAGES:
+            raise ProgressiveItemError(
+                f'{chain["name"]} has {len(stages)} stages, it needs 1 to {MAX_PROGRESSIVE_ITEM_STAGES}'
+            )
+
+        packed_chain = struct.pack(
+            PROGRESSIVE_ITEM_CHAIN_HEADER_FORMAT, chain["itemid"], len(stages)
+        )
+        for stage in stages:
+            packed_chain += pack_progressive_item_stage(stage)
+
+        packed_chains += packed_chain.ljust(PROGRESSIVE_ITEM_CHAIN_SIZE, b"\0")
+
+    # Pad out the whole table so stale data can't follow the last chain
+    return packed_chains.ljust(
+        MAX_PROGRESSIVE_ITEM_CHAINS * PROGRESSIVE_ITEM_CHAIN_SIZE, b"\0"
+    )
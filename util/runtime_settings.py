@@ -0,0 +1,190 @@
+"""
+Generates RandomizerSettings from data/runtime_settings.yaml.
+
+The same schema is used to write the rust struct in
+asm/additions/rust-additions/src/settings.rs and to pack the settings in
+patches/asmpatchhandler.py, so the two can't disagree about the layout.
+
+Run `python -m util.runtime_settings` from the repo root after changing the
+schema to regenerate the rust struct.
+"""
+
+import struct
+from pathlib import Path
+from typing import Callable
+
+import yaml
+
+from util.button_combos import BUTTON_COMBO_SIZE, pack_button_combo
+
+RANDO_ROOT_PATH = Path(__file__).parent.parent
+RUNTIME_SETTINGS_SCHEMA_PATH = RANDO_ROOT_PATH / "data" / "runtime_settings.yaml"
+RUST_SETTINGS_PATH = (
+    RANDO_ROOT_PATH / "asm" / "additions" / "rust-additions" / "src" / "settings.rs"
+)
+
+GENERATED_START = "// BEGIN GENERATED RANDOMIZER SETTINGS\n"
+GENERATED_END = "// END GENERATED RANDOMIZER SETTINGS\n"
+
+# magic, version, size
+RANDOMIZER_SETTINGS_HEADER_FORMAT = "<4sHH"
+
+# type: (struct format, size, alignment)
+FIELD_TYPES = {
+    "u8": ("B", 1, 1),
+    "u16": ("H", 2, 2),
+    "u32": ("I", 4, 4),
+    # Packed by pack_button_combo, aligned like its u32 buttons
+    "ButtonCombo": (f"{BUTTON_COMBO_SIZE}s", BUTTON_COMBO_SIZE, 4),
+}
+
+
+class RuntimeSettingsError(RuntimeError):
+    pass
+
+
+def load_runtime_settings_schema() -> dict:
+    with open(RUNTIME_SETTINGS_SCHEMA_PATH, encoding="utf-8") as schema_file:
+        return yaml.safe_load(schema_file)
+
+
+def get_layout(schema: dict) -> list[tuple[str, str, int]]:
+    """
+    Returns (name, type, count) for every field in the struct in order,
+    including the header and padding. Padding fields have the type "pad".
+    """
+    layout = [("magic", "u32", 1), ("version", "u16", 1), ("size", "u16", 1)]
+    offset = 8
+    padding_count = 0
+
+    for field in schema["fields"]:
+        if field["type"] not in FIELD_TYPES:
+            raise RuntimeSettingsError(
+                f'Unknown type "{field["type"]}" for runtime setting {field["name"]}'
+            )
+
+        count = field.get("count", 1)
+        if len(field["settings"]) != count:
+            raise RuntimeSettingsError(
+                f'Runtime setting {field["name"]} needs {count} settings, got {len(field["settings"])}'
+            )
+
+        _, size, alignment = FIELD_TYPES[field["type"]]
+        if padding := -offset % alignment:
+            layout.append((f"_{padding_count}", "pad", padding))
+            padding_count += 1
+            offset += padding
+
+        layout.append((field["name"], field["type"], count))
+        offset += size * count
+
+    if padding := -offset % 4:
+        layout.append((f"_{padding_count}", "pad", padding))
+
+    return layout
+
+
+def get_randomizer_settings_format(schema: dict) -> str:
+    settings_format = RANDOMIZER_SETTINGS_HEADER_FORMAT
+    for _, field_type, count in get_layout(schema)[3:]:
+        if field_type == "pad":
+            settings_format += f"{count}x"
+        else:
+            settings_format += FIELD_TYPES[field_type][0] * count
+    return settings_format
+
+
+def get_randomizer_settings_size(schema: dict) -> int:
+    return struct.calcsize(get_randomizer_settings_format(schema))
+
+
+def pack_randomizer_settings(schema: dict, get_setting: Callable) -> bytes:
+    """
+    `get_setting` takes a setting name and returns it like `World.setting`.
+    """
+    values = []
+
+    for field in schema["fields"]:
+        for setting_name in field["settings"]:
+            match field["source"]:
+                case "option_index":
+                    values.append(get_setting(setting_name).value_index())
+                case "number":
+                    values.append(int(get_setting(setting_name).value()))
+                case "button_combo":
+                    values.append(
+                        pack_button_combo(
+                            get_setting(f"{setting_name}_combo").value(),
+                            int(get_setting(f"{setting_name}_hold_frames").value()),
+                        )
+                    )
+                case source:
+                    raise RuntimeSettingsError(
+                        f'Unknown source "{source}" for runtime setting {field["name"]}'
+                    )
+
+    return struct.pack(
+        get_randomizer_settings_format(schema),
+        schema["magic"].encode("ascii"),
+        schema["version"],
+        get_randomizer_settings_size(schema),
+        *values,
+    )
+
+
+def generate_rust_settings(schema: dict) -> str:
+    magic = schema["magic"].encode("ascii")
+    layout = get_layout(schema)
+    name_width = max(len(name) for name, _, _ in layout) + 2
+
+    lines = [
+        GENERATED_START,
+        "// Generated from data/runtime_settings.yaml by util/runtime_settings.py,\n",
+        "// don't edit by hand.\n",
+        f"pub const RANDOMIZER_SETTINGS_MAGIC: u32 = 0x{int.from_bytes(magic, 'little'):08X}; // \"{schema['magic']}\" in memory\n",
+        f"pub const RANDOMIZER_SETTINGS_VERSION: u16 = {schema['version']};\n",
+    ]
+
+    for name, field_type, count in layout:
+        if field_type != "pad" and count > 1:
+            lines.append(f"pub const {name.upper()}_COUNT: usize = {count};\n")
+
+    lines += [
+        "\n",
+        "#[repr(C, packed(1))]\n",
+        "#[derive(Copy, Clone)]\n",
+        "pub struct RandomizerSettings {\n",
+    ]
+    for name, field_type, count in layout:
+        if field_type == "pad":
+            rust_type = f"[u8; {count}]"
+        elif count > 1:
+            rust_type = f"[{field_type}; {name.upper()}_COUNT]"
+        else:
+            rust_type = field_type
+        lines.append(f"    pub {name + ':':<{name_width}}{rust_type},\n")
+    lines += [
+        "}\n",
+        f"assert_eq_size!([u8; 0x{get_randomizer_settings_size(schema):X}], RandomizerSettings);\n",
+        GENERATED_END,
+    ]
+
+    return "".join(lines)
+
+
+def replace_generated_section(source: str, generated: str) -> str:
+    start = source.index(GENERATED_START)
+    end = source.index(GENERATED_END) + len(GENERATED_END)
+    return source[:start] + generated + source[end:]
+
+
+def update_rust_settings() -> None:
+    generated = generate_rust_settings(load_runtime_settings_schema())
+    with open(RUST_SETTINGS_PATH, encoding="utf-8") as settings_file:
+        source = settings_file.read()
+    with open(RUST_SETTINGS_PATH, "w", encoding="utf-8", newline="\n") as settings_file:
+        settings_file.write(replace_generated_section(source, generated))
+
+
+if __name__ == "__main__":
+    update_rust_settings()
@@ -0,0 +1,102 @@
+"""
+Helpers for the randomizer's data in the save file.
+
+RandoSaveData in asm/additions/rust-additions/src/savefile.rs lives in a part
+of SaveFile that nothing is known to use. To check that the game really leaves
+it alone, dump a file before and after playing with it and compare the dumps:
+
+    python -m util.savefile before.bin after.bin [--offset OFFSET]
+
+where OFFSET is where the SaveFile starts in the dumps. Any write the game made
+to the randomizer's space is listed.
+"""
+
+import argparse
+import struct
+
+from constants.asmconstants import (
+    RANDO_SAVE_DATA_MAGIC,
+    RANDO_SAVE_DATA_VERSION,
+    SAVEFILE_RANDO_DATA_OFFSET,
+    SAVEFILE_RANDO_DATA_SIZE,
+    SAVEFILE_SIZE,
+)
+
+# magic, version, padding
+RANDO_SAVE_DATA_HEADER_FORMAT = "<4sH2x"
+
+
+class SaveFileError(RuntimeError):
+    pass
+
+
+def get_rando_save_data(dump: bytes, offset: int = 0) -> bytes:
+    if offset < 0 or len(dump) < offset + SAVEFILE_SIZE:
+        raise SaveFileError(
+            f"Dump is too short to hold a save file at {offset:#x} ({len(dump):#x} bytes)"
+        )
+
+    start = offset + SAVEFILE_RANDO_DATA_OFFSET
+    return dump[start : start + SAVEFILE_RANDO_DATA_SIZE]
+
+
+def is_claimed(rando_save_data: bytes) -> bool:
+    return struct.unpack_from(RANDO_SAVE_DATA_HEADER_FORMAT, rando_save_data) == (
+        RANDO_SAVE_DATA_MAGIC,
+        RANDO_SAVE_DATA_VERSION,
+    )
+
+
+def find_writes(before: bytes, after: bytes, offset: int = 0) -> list[tuple[int, int]]:
+    """
+    Returns (offset, length) for every run of bytes that differs between the
+    randomizer's space in the two dumps, relative to the start of RandoSaveData.
+    """
+    before_data = get_rando_save_data(before, offset)
+    after_data = get_rando_save_data(after, offset)
+
+    writes = []
+    run_start = None
+    for index in range(SAVEFILE_RANDO_DATA_SIZE + 1):
+        differs = (
+            index < SAVEFILE_RANDO_DATA_SIZE
+            and before_data[index] != after_data[index]
+        )
+        if differs and run_start is None:
+            run_start = index
+        elif not differs and run_start is not None:
+            writes.append((run_start, index - run_start))
+            run_start = None
+
+    return writes
+
+
+def main():
+    parser = argparse.ArgumentParser(
+        description="Lists writes to the randomizer's space between two save dumps"
+    )
+    parser.add_argument("before")
+    parser.add_argument("after")
+    parser.add_argument("--offset", type=lambda value: int(value, 0), default=0)
+    args = parser.parse_args()
+
+    with open(args.before, "rb") as file:
+        before = file.read()
+    with open(args.after, "rb") as file:
+        after = file.read()
+
+    for name, dump in (("before", before), ("after", after)):
+        if is_claimed(get_rando_save_data(dump, args.offset)):
+            print(f"The {name} dump has randomizer data, compare vanilla saves")
+
+    writes = find_writes(before, after, args.offset)
+    for write_offset, length in writes:
+        print(
+            f"{length:#x} bytes written at {SAVEFILE_RANDO_DATA_OFFSET + write_offset:#x}"
+        )
+    if not writes:
+        print("Nothing was written to the randomizer's space")
+
+
+if __name__ == "__main__":
+    main()
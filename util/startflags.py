@@ -0,0 +1,204 @@
+"""
+Packs the startflags read by handle_startflags in
+asm/additions/rust-additions/src/flag.rs.
+
+The startflags are a header followed by tagged records so a section can't be
+misread as a different flag space:
+
+    header: magic, version, length of the records in bytes
+    record: tag, length of the entries in bytes, entries
+
+Every entry in a record has the same layout, which depends on the tag.
+`unpack_startflags` mirrors the rust parser so the format can be tested here.
+"""
+
+import struct
+from typing import NamedTuple
+
+from constants.asmconstants import (
+    MAX_STARTFLAGS_SIZE,
+    STARTFLAGS_MAGIC,
+    STARTFLAGS_VERSION,
+)
+
+# magic, version, length
+STARTFLAGS_HEADER_FORMAT = "<4sHH"
+STARTFLAGS_HEADER_SIZE = struct.calcsize(STARTFLAGS_HEADER_FORMAT)
+
+# tag, length
+STARTFLAG_RECORD_HEADER_FORMAT = "<BB"
+STARTFLAG_RECORD_HEADER_SIZE = struct.calcsize(STARTFLAG_RECORD_HEADER_FORMAT)
+MAX_STARTFLAG_RECORD_LENGTH = 0xFF
+
+# Matches STARTFLAG_TAG in flag.rs
+STARTFLAG_TAG_STORYFLAG = 1
+STARTFLAG_TAG_SCENEFLAG = 2
+STARTFLAG_TAG_ITEMFLAG = 3
+STARTFLAG_TAG_DUNGEONFLAG = 4
+STARTFLAG_TAG_TEMPFLAG = 5
+STARTFLAG_TAG_ZONEFLAG = 6
+STARTFLAG_TAG_COUNTER = 7
+STARTFLAG_TAG_LOADOUT_ITEM = 8
+STARTFLAG_TAG_STAGE_OBJECT_FLAG = 9
+
+STARTFLAG_ENTRY_FORMATS = {
+    STARTFLAG_TAG_STORYFLAG: "<H",  # flag
+    STARTFLAG_TAG_SCENEFLAG: "<BB",  # sceneindex, flag
+    STARTFLAG_TAG_ITEMFLAG: "<H",  # flag
+    STARTFLAG_TAG_DUNGEONFLAG: "<BB",  # sceneindex, flag
+    STARTFLAG_TAG_TEMPFLAG: "<H",  # flag
+    STARTFLAG_TAG_ZONEFLAG: "<BB",  # roomid, flag
+    STARTFLAG_TAG_COUNTER: "<HH",  # counter, value
+    STARTFLAG_TAG_LOADOUT_ITEM: "<BBHH",  # storage, slot, itemid, extra
+    STARTFLAG_TAG_STAGE_OBJECT_FLAG: "<8sH",  # stage, objectid
+}
+
+MAX_SCENEINDEX = 25
+MAX_ROOMID = 62
+# Matches ITEMFLAG_COUNT in flag.rs
+ITEMFLAG_COUNT = 512
+
+
+class StartflagsError(RuntimeError):
+    pass
+
+
+class StartflagRecord(NamedTuple):
+    tag: int
+    entries: list[tuple]
+
+
+def pack_startflag_records(tag: int, entries: list[tuple]) -> bytes:
+    """
+    Packs entries of one tag, split over as many records as needed to fit the
+    record length.
+    """
+    entry_format = STARTFLAG_ENTRY_FORMATS[tag]
+    entry_size = struct.calcsize(entry_format)
+    entries_per_record = MAX_STARTFLAG_RECORD_LENGTH // entry_size
+
+    packed_records = b""
+    for start in range(0, len(entries), entries_per_record):
+        record_entries = entries[start : start + entries_per_record]
+        packed_records += struct.pack(
+            STARTFLAG_RECORD_HEADER_FORMAT, tag, len(record_entries) * entry_size
+        )
+        for entry in record_entries:
+            packed_records += struct.pack(entry_format, *entry)
+
+    return packed_records
+
+
+def pack_startflags(records: list[StartflagRecord]) -> bytes:
+    packed_records = b"".join(
+        pack_startflag_records(record.tag, record.entries) for record in records
+    )
+
+    packed_startflags = (
+        struct.pack(
+            STARTFLAGS_HEADER_FORMAT,
+            STARTFLAGS_MAGIC,
+            STARTFLAGS_VERSION,
+            len(packed_records),
+        )
+        + packed_records
+    )
+
+    if len(packed_startflags) > MAX_STARTFLAGS_SIZE:
+        raise StartflagsError(
+            f"Too many startflags ({len(packed_startflags)} bytes), the maximum is {MAX_STARTFLAGS_SIZE}"
+        )
+
+    # Pad out the whole block so stale data can't follow the last record
+    return packed_startflags.ljust(MAX_STARTFLAGS_SIZE, b"\0")
+
+
+def get_stage_object_flags(
+    stage_object_flags: dict[str, list[int]], start_stage: str
+) -> list[tuple[bytes, int]]:
+    """
+    Returns the stage object flag entries for the stage the file starts in.
+    Stage object flags only belong to the loaded stage, so the game can't
+    write any other stage's flags when the file is created.
+    """
+    return [
+        (start_stage.encode("ascii"), objectid)
+        for objectid in stage_object_flags.get(start_stage) or []
+    ]
+
+
+def unpack_startflags(
+    data: bytes, start_stage: str | None = None
+) -> tuple[list[StartflagRecord], list[str]]:
+    """
+    Returns the records the game would apply and the messages it would log for
+    anything it skips. `start_stage` is the stage name from the warp to start
+    info, stage object flags for any other stage are skipped.
+    """
+    magic, version, length = struct.unpack_from(STARTFLAGS_HEADER_FORMAT, data)
+    if magic != STARTFLAGS_MAGIC or version != STARTFLAGS_VERSION:
+        return [], [f"Unknown startflags version: {version}"]
+
+    records = []
+    skipped = []
+    length = min(length, len(data) - STARTFLAGS_HEADER_SIZE)
+    offset = 0
+
+    while offset + STARTFLAG_RECORD_HEADER_SIZE <= length:
+        tag, record_length = struct.unpack_from(
+            STARTFLAG_RECORD_HEADER_FORMAT, data, STARTFLAGS_HEADER_SIZE + offset
+        )
+        entries_start = offset + STARTFLAG_RECORD_HEADER_SIZE
+        offset = entries_start + record_length
+
+        if offset > length:
+            skipped.append(f"Startflag record overruns the startflags: {tag}")
+            break
+
+        if tag not in STARTFLAG_ENTRY_FORMATS:
+            skipped.append(f"Skipping unknown startflag tag: {tag}")
+            continue
+
+        entry_format = STARTFLAG_ENTRY_FORMATS[tag]
+        entry_size = struct.calcsize(entry_format)
+        if record_length % entry_size:
+            skipped.append(f"Startflag record has a partial entry: {tag}")
+
+        entries = []
+        for entry_offset in range(entries_start, offset - entry_size + 1, entry_size):
+            entry = struct.unpack_from(
+                entry_format, data, STARTFLAGS_HEADER_SIZE + entry_offset
+            )
+
+            if tag in (STARTFLAG_TAG_SCENEFLAG, STARTFLAG_TAG_DUNGEONFLAG):
+                if entry[0] > MAX_SCENEINDEX:
+                    skipped.append(f"Skipping startflag with invalid scene: {entry[0]}")
+                    continue
+
+            if tag == STARTFLAG_TAG_ZONEFLAG and entry[0] > MAX_ROOMID:
+                skipped.append(f"Skipping zoneflag with invalid room: {entry[0]}")
+                continue
+
+            if tag == STARTFLAG_TAG_ITEMFLAG and entry[0] >= ITEMFLAG_COUNT:
+                skipped.append(f"Skipping startflag with invalid itemflag: {entry[0]}")
+                continue
+
+            if tag == STARTFLAG_TAG_COUNTER and entry[0] >= ITEMFLAG_COUNT:
+                skipped.append(f"Skipping startflag with invalid counter: {entry[0]}")
+                continue
+
+            if (
+                tag == STARTFLAG_TAG_STAGE_OBJECT_FLAG
+                and start_stage is not None
+                and entry[0].rstrip(b"\0") != start_stage.encode("ascii")
+            ):
+                skipped.append(
+                    f"Skipping stage object flag for another stage: {entry[1]}"
+                )
+                continue
+
+            entries.append(entry)
+
+        records.append(StartflagRecord(tag, entries))
+
+    return records, skipped
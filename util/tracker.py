@@ -0,0 +1,339 @@
+"""
+Host-side readers for the location check log, the tracker state block and the
+entrance history.
+
+The game side lives in asm/additions/rust-additions/src/tracker.rs (and
+entrance.rs for the entrance history). This also packs RANDOMIZED_SCENEFLAGS,
+which tells the game which items with sceneflags are randomized locations.
+"""
+
+import struct
+from typing import Callable, NamedTuple
+
+from constants.asmconstants import (
+    ENTRANCE_HISTORY_CAPACITY,
+    LOCATION_FLAG_COUNT,
+    LOCATION_LOG_CAPACITY,
+    LOCATION_LOG_MAGIC,
+    LOCATION_LOG_VERSION,
+    MAX_RANDOMIZED_SCENEFLAGS,
+    TRACKER_STATE_MAGIC,
+    TRACKER_STATE_VERSION,
+)
+
+# magic, version, capacity, write_cursor, frame
+LOCATION_LOG_HEADER_FORMAT = "<4sHHII"
+# location_id, itemid, padding, frame
+LOCATION_LOG_RECORD_FORMAT = "<IH2xI"
+
+LOCATION_LOG_HEADER_SIZE = struct.calcsize(LOCATION_LOG_HEADER_FORMAT)
+LOCATION_LOG_RECORD_SIZE = struct.calcsize(LOCATION_LOG_RECORD_FORMAT)
+LOCATION_LOG_SIZE = (
+    LOCATION_LOG_HEADER_SIZE + LOCATION_LOG_RECORD_SIZE * LOCATION_LOG_CAPACITY
+)
+
+# Matches LOCATION_KIND in tracker.rs
+LOCATION_KIND_SCENEFLAG = 0
+LOCATION_KIND_DUNGEONFLAG = 1
+LOCATION_KIND_CHEST = 2
+LOCATION_KIND_EVENT = 3
+LOCATION_KIND_LOCATION_FLAG = 4
+
+# stage, sceneflag, padding
+RANDOMIZED_SCENEFLAG_FORMAT = "<8sBx"
+RANDOMIZED_SCENEFLAG_SIZE = struct.calcsize(RANDOMIZED_SCENEFLAG_FORMAT)
+
+
+class TrackerError(RuntimeError):
+    pass
+
+
+class LocationCheck(NamedTuple):
+    location_id: int
+    itemid: int
+    frame: int
+
+
+def location_id(kind: int, sceneindex: int, flag: int) -> int:
+    return (kind << 24) | ((sceneindex & 0xFF) << 16) | (flag & 0xFFFF)
+
+
+def event_location_id(event_file: str, flow_index: int) -> int:
+    # Only the number the event file's name starts with is used
+    event_file_number = int(event_file.split("-")[0])
+    return (
+        (LOCATION_KIND_EVENT << 24)
+        | ((event_file_number & 0xFFF) << 12)
+        | (flow_index & 0xFFF)
+    )
+
+
+def pack_randomized_sceneflags(sceneflags: list[tuple[str, int]]) -> bytes:
+    # The last entry is left empty to end the table
+    if len(sceneflags) >= MAX_RANDOMIZED_SCENEFLAGS:
+        raise TrackerError(
+            f"Too many randomized sceneflags ({len(sceneflags)}), the maximum is {MAX_RANDOMIZED_SCENEFLAGS - 1}"
+        )
+
+    packed_sceneflags = b""
+    for stage, sceneflag in sceneflags:
+        if not stage or len(stage) > 8:
+            raise TrackerError(f"Bad stage name for a randomized sceneflag: {stage}")
+        if not 0 <= sceneflag < 0xFF:
+            raise TrackerError(f"Bad randomized sceneflag in {stage}: {sceneflag}")
+
+        packed_sceneflags += struct.pack(
+            RANDOMIZED_SCENEFLAG_FORMAT, stage.encode("ascii"), sceneflag
+        )
+
+    # Pad out the whole table so stale data can't follow the last entry
+    return packed_sceneflags.ljust(
+        MAX_RANDOMIZED_SCENEFLAGS * RANDOMIZED_SCENEFLAG_SIZE, b"\0"
+    )
+
+
+def empty_location_log() -> bytes:
+    header = struct.pack(
+        LOCATION_LOG_HEADER_FORMAT,
+        LOCATION_LOG_MAGIC,
+        LOCATION_LOG_VERSION,
+        LOCATION_LOG_CAPACITY,
+        0,
+        0,
+    )
+    return header + bytes(LOCATION_LOG_RECORD_SIZE * LOCATION_LOG_CAPACITY)
+
+
+def record_offset(cursor: int) -> int:
+    slot = cursor % LOCATION_LOG_CAPACITY
+    return LOCATION_LOG_HEADER_SIZE + slot * LOCATION_LOG_RECORD_SIZE
+
+
+class LocationLogReader:
+    """
+    Consumes new records from the location check log. `read` takes an offset
+    relative to the start of the log and a size.
+    """
+
+    def __init__(self, read: Callable[[int, int], bytes]) -> None:
+        self.read = read
+        self.cursor = 0
+        # Set when records were overwritten before they could be read, the
+        # caller should resync from the save's flags
+        self.lost_records = False
+
+    def update(self) -> list[LocationCheck] | None:
+        magic, version, capacity, write_cursor, _ = struct.unpack(
+            LOCATION_LOG_HEADER_FORMAT, self.read(0, LOCATION_LOG_HEADER_SIZE)
+        )
+
+        if (
+            magic != LOCATION_LOG_MAGIC
+            or version != LOCATION_LOG_VERSION
+            or capacity != LOCATION_LOG_CAPACITY
+        ):
+            return None
+
+        # The game was restarted
+        if write_cursor < self.cursor:
+            self.cursor = 0
+
+        if write_cursor - self.cursor > capacity:
+            self.lost_records = True
+            self.cursor = write_cursor - capacity
+
+        records = []
+        while self.cursor < write_cursor:
+            records.append(
+                LocationCheck._make(
+                    struct.unpack(
+                        LOCATION_LOG_RECORD_FORMAT,
+                        self.read(
+                            record_offset(self.cursor), LOCATION_LOG_RECORD_SIZE
+                        ),
+                    )
+                )
+            )
+            self.cursor += 1
+
+        return records
+
+
+class LocationLogGameMock:
+    """Mirrors log_location_check() from tracker.rs."""
+
+    def __init__(self) -> None:
+        self.memory = bytearray(empty_location_log())
+        self.frame = 0
+
+    def read(self, offset: int, size: int) -> bytes:
+        return bytes(self.memory[offset : offset + size])
+
+    def log_location_check(self, location_id: int, itemid: int) -> None:
+        _, _, _, write_cursor, _ = struct.unpack_from(
+            LOCATION_LOG_HEADER_FORMAT, self.memory
+        )
+        struct.pack_into(
+            LOCATION_LOG_RECORD_FORMAT,
+            self.memory,
+            record_offset(write_cursor),
+            location_id,
+            itemid,
+            self.frame,
+        )
+        struct.pack_into("<II", self.memory, 8, write_cursor + 1, self.frame)
+
+
+# magic, version, size, sequence
+TRACKER_STATE_HEADER_FORMAT = "<4sHHI"
+# stage_name, room, layer, entrance, night, sword, in_game, health,
+# health_capacity, stamina, minigame, pouch_items, dungeon_item_flags,
+# small_keys
+TRACKER_STATE_FORMAT = TRACKER_STATE_HEADER_FORMAT + "8sBBBBB?2xHHIi8i26s26s4x"
+
+TRACKER_STATE_HEADER_SIZE = struct.calcsize(TRACKER_STATE_HEADER_FORMAT)
+TRACKER_STATE_SIZE = struct.calcsize(TRACKER_STATE_FORMAT)
+
+SEQUENCE_OFFSET = 8
+
+
+class TrackerState(NamedTuple):
+    stage_name: str
+    room: int
+    layer: int
+    entrance: int
+    night: bool
+    sword: int
+    in_game: bool
+    health: int
+    health_capacity: int
+    stamina: int
+    minigame: int
+    pouch_items: tuple[int, ...]
+    dungeon_item_flags: bytes
+    small_keys: bytes
+
+
+def empty_tracker_state() -> bytes:
+    header = struct.pack(
+        TRACKER_STATE_HEADER_FORMAT,
+        TRACKER_STATE_MAGIC,
+        TRACKER_STATE_VERSION,
+        TRACKER_STATE_SIZE,
+        0,
+    )
+    return header + bytes(TRACKER_STATE_SIZE - TRACKER_STATE_HEADER_SIZE)
+
+
+def unpack_tracker_state(data: bytes) -> TrackerState:
+    fields = struct.unpack(TRACKER_STATE_FORMAT, data)[4:]
+    stage_name = fields[0].split(b"\0", 1)[0].decode("ascii", "replace")
+    return TrackerState(
+        stage_name,
+        *fields[1:4],
+        bool(fields[4]),
+        *fields[5:11],
+        tuple(fields[11:19]),
+        *fields[19:],
+    )
+
+
+def read_tracker_state(
+    read: Callable[[int, int], bytes], retries: int = 5
+) -> TrackerState | None:
+    """
+    Returns None if the block isn't there (or is from a different version), or
+    if the game kept writing it while it was being read.
+    """
+    for _ in range(retries):
+        magic, version, size, sequence = struct.unpack(
+            TRACKER_STATE_HEADER_FORMAT, read(0, TRACKER_STATE_HEADER_SIZE)
+        )
+
+        if (
+            magic != TRACKER_STATE_MAGIC
+            or version != TRACKER_STATE_VERSION
+            or size != TRACKER_STATE_SIZE
+        ):
+            return None
+
+        # Odd means the game is in the middle of an update
+        if sequence & 1:
+            continue
+
+        data = read(0, TRACKER_STATE_SIZE)
+        (sequence_after,) = struct.unpack("<I", read(SEQUENCE_OFFSET, 4))
+
+        if sequence_after == sequence:
+            return unpack_tracker_state(data)
+
+    return None
+
+
+# count
+ENTRANCE_HISTORY_HEADER_FORMAT = "<I"
+# from_stage_name, from_room, from_layer, from_arrival_entrance, kind,
+# to_stage_name, to_room, to_layer, to_entrance, to_night
+ENTRANCE_TRANSITION_FORMAT = "<8sBBBB8sBBBB"
+
+ENTRANCE_HISTORY_HEADER_SIZE = struct.calcsize(ENTRANCE_HISTORY_HEADER_FORMAT)
+ENTRANCE_TRANSITION_SIZE = struct.calcsize(ENTRANCE_TRANSITION_FORMAT)
+ENTRANCE_HISTORY_SIZE = (
+    ENTRANCE_HISTORY_HEADER_SIZE + ENTRANCE_TRANSITION_SIZE * ENTRANCE_HISTORY_CAPACITY
+)
+
+# Matches TRANSITION_KIND in entrance.rs
+TRANSITION_KIND_ENTRANCE = 0
+TRANSITION_KIND_FI_WARP = 1
+
+
+class EntranceTransition(NamedTuple):
+    from_stage_name: str
+    from_room: int
+    from_layer: int
+    # The entrance the from stage was entered through, not the exit taken
+    from_arrival_entrance: int
+    kind: int
+    to_stage_name: str
+    to_room: int
+    to_layer: int
+    to_entrance: int
+    to_night: bool
+
+
+def unpack_entrance_history(data: bytes) -> list[EntranceTransition]:
+    """
+    Takes the entrance history from a save file (at
+    SAVEFILE_ENTRANCE_HISTORY_OFFSET) and returns the transitions it still
+    holds, oldest first.
+    """
+    (count,) = struct.unpack_from(ENTRANCE_HISTORY_HEADER_FORMAT, data)
+
+    transitions = []
+    for index in range(max(0, count - ENTRANCE_HISTORY_CAPACITY), count):
+        fields = struct.unpack_from(
+            ENTRANCE_TRANSITION_FORMAT,
+            data,
+            ENTRANCE_HISTORY_HEADER_SIZE
+            + (index % ENTRANCE_HISTORY_CAPACITY) * ENTRANCE_TRANSITION_SIZE,
+        )
+        transitions.append(
+            EntranceTransition(
+                fields[0].split(b"\0", 1)[0].decode("ascii", "replace"),
+                *fields[1:5],
+                fields[5].split(b"\0", 1)[0].decode("ascii", "replace"),
+                *fields[6:9],
+                bool(fields[9]),
+            )
+        )
+
+    return transitions
+
+
+def unpack_location_flags(data: bytes) -> set[int]:
+    """
+    Takes the location flags from a save (at SAVEFILE_LOCATION_FLAGS_OFFSET) and
+    returns the ids of the ones that are set.
+    """
+    flags = int.from_bytes(data[: LOCATION_FLAG_COUNT // 8], "little")
+    return {flag for flag in range(LOCATION_FLAG_COUNT) if flags >> flag & 1}
@@ -0,0 +1,135 @@
+"""
+Helpers for data/warp_destinations.yaml.
+
+The destinations are packed for warp_to_destination in
+asm/additions/rust-additions/src/entrance.rs and also used to build Fi's warp
+menu, so a destination's index in the list is the param1 of its event
+command.
+"""
+
+import struct
+from typing import Callable, NamedTuple
+
+from constants.asmconstants import MAX_WARP_DESTINATIONS, SCENE_NAME_TO_SCENE_INDEX
+
+# Matches ER_FLAG_TYPE in entrance.rs
+FLAG_TYPE_NONE = 0
+FLAG_TYPE_STORYFLAG = 1
+FLAG_TYPE_SCENEFLAG = 2
+
+# stage_name, room, layer, entrance, night,
+# unlock (flag_type, value, sceneindex, count, flag), unlock_on_arrival
+WARP_DESTINATION_FORMAT = "<8sBBBBBBBBHBx"
+WARP_DESTINATION_SIZE = struct.calcsize(WARP_DESTINATION_FORMAT)
+
+
+class WarpDestinationError(RuntimeError):
+    pass
+
+
+class WarpDestination(NamedTuple):
+    group: str
+    name: str
+    spawn_info: dict
+    flag_type: int
+    sceneindex: int
+    flag: int
+    unlock_on_arrival: bool
+
+
+def unlock_flag(destination: dict, bird_statue_data: dict) -> tuple[int, int, int]:
+    unlock = destination.get("unlock")
+
+    if unlock is None:
+        return FLAG_TYPE_NONE, 0, 0
+
+    if unlock == "bird_statue":
+        unlock = bird_statue_data[destination["name"]]
+        if unlock["flag_space"] == "Story":
+            unlock = {"storyflag": unlock["flag"]}
+        else:
+            unlock = {
+                "sceneflag": {"scene": unlock["flag_space"], "flag": unlock["flag"]}
+            }
+
+    if "storyflag" in unlock:
+        return FLAG_TYPE_STORYFLAG, 0, unlock["storyflag"]
+
+    if "sceneflag" in unlock:
+        scene = unlock["sceneflag"]["scene"]
+        if scene not in SCENE_NAME_TO_SCENE_INDEX:
+            raise WarpDestinationError(
+                f'Unknown scene "{scene}" for warp destination {destination["name"]}'
+            )
+        return (
+            FLAG_TYPE_SCENEFLAG,
+            SCENE_NAME_TO_SCENE_INDEX[scene],
+            unlock["sceneflag"]["flag"],
+        )
+
+    raise WarpDestinationError(
+        f'Unknown unlock {unlock} for warp destination {destination["name"]}'
+    )
+
+
+def get_warp_destinations(
+    warp_destination_data: dict,
+    bird_statue_data: dict,
+    get_spawn_info: Callable[[str], dict],
+) -> list[WarpDestination]:
+    """
+    `get_spawn_info` takes an entrance name (e.g. "Deep Woods -> Skyview Entry")
+    and returns its spawn info.
+    """
+    destinations = []
+
+    for group, group_destinations in warp_destination_data.items():
+        for destination in group_destinations:
+            destinations.append(
+                WarpDestination(
+                    group,
+                    destination["name"],
+                    get_spawn_info(destination["entrance"]),
+                    *unlock_flag(destination, bird_statue_data),
+                    destination.get("unlock_on_arrival", False),
+                )
+            )
+
+    return destinations
+
+
+def pack_warp_destinations(destinations: list[WarpDestination]) -> bytes:
+    # The last destination is left empty to end the table
+    if len(destinations) >= MAX_WARP_DESTINATIONS:
+        raise WarpDestinationError(
+            f"Too many warp destinations ({len(destinations)}), the maximum is {MAX_WARP_DESTINATIONS - 1}"
+        )
+
+    packed_destinations = b""
+    for destination in destinations:
+        spawn_info = destination.spawn_info
+
+        if len(spawn_info["stage"]) >= 8:
+            raise WarpDestinationError(
+                f'Stage name "{spawn_info["stage"]}" is too long'
+            )
+
+        packed_destinations += struct.pack(
+            WARP_DESTINATION_FORMAT,
+            spawn_info["stage"].encode("ascii"),
+            spawn_info["room"],
+            spawn_info["layer"],
+            spawn_info["entrance"],
+            0,  # night
+            destination.flag_type,
+            1,  # flag must be set
+            destination.sceneindex,
+            0 if destination.flag_type == FLAG_TYPE_NONE else 1,
+            destination.flag,
+            1 if destination.unlock_on_arrival else 0,
+        )
+
+    # Pad out the whole table so stale data can't follow the last destination
+    return packed_destinations.ljust(
+        MAX_WARP_DESTINATIONS * WARP_DESTINATION_SIZE, b"\0"
+    )